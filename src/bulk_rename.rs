@@ -0,0 +1,97 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+const PREVIEW_ROWS: usize = 8;
+
+/// renames several items at once from a template, `{name}` is the old name and `{n}` a counter starting at 1.
+/// the extension of every item is kept
+pub struct BulkRename {
+    paths: Vec<PathBuf>,
+    template: String
+}
+
+impl BulkRename {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self {
+            paths,
+            template: String::from("{name} {n}")
+        }
+    }
+
+    pub fn new_names(&self) -> Vec<String> {
+        self.paths.iter().enumerate().map(|(index, path)| {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            let name = self.template.replace("{name}", &stem).replace("{n}", &(index + 1).to_string());
+            match path.extension() {
+                Some(extension) if !path.is_dir() => format!("{name}.{}", extension.to_string_lossy()),
+                _ => name
+            }
+        }).collect()
+    }
+
+    fn problem(&self, new_names: &[String]) -> Option<String> {
+        let mut seen = HashSet::new();
+        for (path, name) in self.paths.iter().zip(new_names) {
+            if name.is_empty() || name.contains('/') {
+                return Some(format!("\"{name}\" is not a valid name"));
+            }
+            if !seen.insert(name) {
+                return Some(format!("more than one item would be named \"{name}\""));
+            }
+            let target = path.with_file_name(name);
+            if target != *path && target.exists() {
+                return Some(format!("{name} already exists"));
+            }
+        }
+        None
+    }
+
+    /// returns the renames to make when applied, or None while the dialog stays open
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<Vec<(PathBuf, String)>> {
+        let mut open = true;
+        let mut result = None;
+        let center = ctx.screen_rect().center();
+        egui::Window::new(format!("Rename {} items", self.paths.len()))
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .default_pos(center)
+            .pivot(egui::Align2::CENTER_CENTER)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("New name");
+                    ui.text_edit_singleline(&mut self.template);
+                });
+                ui.weak("{name} is the current name and {n} counts from 1");
+                ui.separator();
+                let new_names = self.new_names();
+                for (path, name) in self.paths.iter().zip(&new_names).take(PREVIEW_ROWS) {
+                    ui.label(format!("{}  →  {name}", file_name(path)));
+                }
+                if self.paths.len() > PREVIEW_ROWS {
+                    ui.weak(format!("and {} more", self.paths.len() - PREVIEW_ROWS));
+                }
+                let problem = self.problem(&new_names);
+                if let Some(problem) = &problem {
+                    ui.colored_label(ui.visuals().error_fg_color, problem);
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                    ui.style_mut().spacing.button_padding = (24.0, 4.0).into();
+                    if ui.button("Cancel").clicked() {
+                        result = Some(Vec::new());
+                    }
+                    if ui.add_enabled(problem.is_none(), egui::Button::new("Rename")).clicked() {
+                        result = Some(self.paths.iter().cloned().zip(new_names).collect());
+                    }
+                });
+            });
+        if !open {
+            return Some(Vec::new());
+        }
+        result
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
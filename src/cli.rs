@@ -0,0 +1,94 @@
+use std::env;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use crate::location;
+
+pub const USAGE: &str = "\
+Usage: file-explorer [OPTION]... [PATH]...
+Opens each folder in a tab of its own, a file is shown selected in its folder.
+Without a path the tabs of the last session are opened again.
+
+Options:
+      --select FILE   open the folder of the file with the file selected
+      --new-window    open a new window with only the given paths, or the working directory
+      --dual-pane     open with the split view, the last path goes to the right pane
+  -h, --help          show this help and exit
+  -V, --version       show the version and exit
+";
+
+pub enum Command {
+    Run(Arguments),
+    Help,
+    Version
+}
+
+#[derive(Default)]
+pub struct Arguments {
+    /// the folders to open, one tab each
+    pub directories: Vec<PathBuf>,
+    /// the files to show selected in the tab of their folder
+    pub selected: Vec<PathBuf>,
+    pub new_window: bool,
+    pub dual_pane: bool
+}
+
+impl Arguments {
+    /// files open their folder with them selected, paths that don't exist are an error.
+    /// `~` and variables were expanded by the shell already, so only `.` and `..` are resolved
+    fn add_path(&mut self, path: PathBuf, select: bool) -> Result<(), String> {
+        let path = if path.is_absolute() {
+            location::normalize(&path)
+        }
+        else {
+            let current = env::current_dir().map_err(|error| format!("couldn't get the working directory: {error}"))?;
+            location::normalize(&current.join(path))
+        };
+        if !path.exists() {
+            return Err(format!("{} doesn't exist", path.display()));
+        }
+        let directory = if select || !path.is_dir() {
+            let Some(parent) = path.parent() else {
+                return Err(format!("{} has no folder to be selected in", path.display()));
+            };
+            self.selected.push(path.clone());
+            parent.to_path_buf()
+        }
+        else {
+            path
+        };
+        if !self.directories.contains(&directory) {
+            self.directories.push(directory);
+        }
+        Ok(())
+    }
+}
+
+pub fn parse(args: impl IntoIterator<Item = OsString>) -> Result<Command, String> {
+    let mut arguments = Arguments::default();
+    let mut args = args.into_iter();
+    let mut only_paths = false;
+    while let Some(arg) = args.next() {
+        if only_paths {
+            arguments.add_path(PathBuf::from(arg), false)?;
+            continue;
+        }
+        match arg.to_str() {
+            Some("-h" | "--help") => return Ok(Command::Help),
+            Some("-V" | "--version") => return Ok(Command::Version),
+            Some("--new-window") => arguments.new_window = true,
+            Some("--dual-pane") => arguments.dual_pane = true,
+            Some("--select") => {
+                let file = args.next().ok_or("--select needs the file to select")?;
+                arguments.add_path(PathBuf::from(file), true)?;
+            },
+            Some("--") => only_paths = true,
+            Some(option) if option.starts_with("--select=") => {
+                arguments.add_path(PathBuf::from(&option["--select=".len()..]), true)?;
+            },
+            Some(option) if option.starts_with('-') && option != "-" => return Err(format!("unknown option {option}")),
+            _ => arguments.add_path(PathBuf::from(arg), false)?
+        }
+    }
+    Ok(Command::Run(arguments))
+}
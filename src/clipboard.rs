@@ -0,0 +1,18 @@
+use std::path::PathBuf;
+
+pub enum ClipboardMode {
+    Copy,
+    Cut
+}
+
+/// an internal clipboard for files, it is not shared with other applications
+pub struct Clipboard {
+    pub paths: Vec<PathBuf>,
+    pub mode: ClipboardMode
+}
+
+impl Clipboard {
+    pub fn new(paths: Vec<PathBuf>, mode: ClipboardMode) -> Self {
+        Self { paths, mode }
+    }
+}
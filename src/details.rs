@@ -0,0 +1,335 @@
+use std::cmp::Ordering;
+
+use egui::emath::Rangef;
+use serde::{Deserialize, Serialize};
+
+use crate::file_list::FileListItem;
+use crate::metadata;
+use crate::widgets;
+
+const MIN_COLUMN_WIDTH: f32 = 40.0;
+const RESIZE_HANDLE_WIDTH: f32 = 6.0;
+const CELL_PADDING: f32 = 4.0;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Name,
+    Size,
+    Type,
+    Modified,
+    Created,
+    Accessed,
+    Owner,
+    Group,
+    Permissions
+}
+
+impl Column {
+    pub const ALL: [Column; 9] = [
+        Column::Name,
+        Column::Size,
+        Column::Type,
+        Column::Modified,
+        Column::Created,
+        Column::Accessed,
+        Column::Owner,
+        Column::Group,
+        Column::Permissions
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Column::Name => "name",
+            Column::Size => "size",
+            Column::Type => "type",
+            Column::Modified => "modified",
+            Column::Created => "created",
+            Column::Accessed => "accessed",
+            Column::Owner => "owner",
+            Column::Group => "group",
+            Column::Permissions => "permissions",
+        }
+    }
+
+    fn default_width(self) -> f32 {
+        match self {
+            Column::Name => 250.0,
+            Column::Size | Column::Owner | Column::Group => 80.0,
+            Column::Type | Column::Permissions => 100.0,
+            Column::Modified | Column::Created | Column::Accessed => 130.0,
+        }
+    }
+
+    fn default_visible(self) -> bool {
+        matches!(self, Column::Name | Column::Size | Column::Type | Column::Modified)
+    }
+
+    fn index(self) -> usize {
+        Column::ALL.iter().position(|column| *column == self).unwrap_or(0)
+    }
+
+    /// the text of a cell, the name column is drawn by the file list itself
+    pub fn text(self, item: &FileListItem) -> String {
+        let Some(metadata) = &item.metadata else {
+            return String::new();
+        };
+        match self {
+            Column::Name => item.name(),
+            Column::Size => if metadata.is_dir { String::new() } else { widgets::format_size(metadata.size) },
+            Column::Type => metadata.type_name(&item.path),
+            Column::Modified => metadata::format_time(metadata.modified),
+            Column::Created => metadata::format_time(metadata.created),
+            Column::Accessed => metadata::format_time(metadata.accessed),
+            Column::Owner => metadata.owner(),
+            Column::Group => metadata.group(),
+            Column::Permissions => metadata.permissions(),
+        }
+    }
+
+    fn right_aligned(self) -> bool {
+        self == Column::Size
+    }
+}
+
+/// the widths and visibility of the details view columns, the name column can't be hidden
+pub struct Columns {
+    widths: [f32; 9],
+    visible: [bool; 9]
+}
+
+impl Columns {
+    pub fn new() -> Self {
+        Self {
+            widths: Column::ALL.map(Column::default_width),
+            visible: Column::ALL.map(Column::default_visible)
+        }
+    }
+
+    /// the visible columns with their horizontal ranges, starting at `left`
+    pub fn layout(&self, left: f32) -> Vec<(Column, Rangef)> {
+        let mut x = left;
+        Column::ALL.iter()
+            .filter(|column| self.visible[column.index()])
+            .map(|column| {
+                let width = self.widths[column.index()];
+                let range = Rangef::new(x, x + width);
+                x += width;
+                (*column, range)
+            })
+            .collect()
+    }
+
+    pub fn name_width(&self) -> f32 {
+        self.widths[Column::Name.index()]
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Ascending,
+    Descending
+}
+
+#[derive(Clone, Copy)]
+pub struct Sorting {
+    pub column: Column,
+    pub order: SortOrder,
+    pub folders_first: bool
+}
+
+impl Sorting {
+    pub fn new() -> Self {
+        Self {
+            column: Column::Name,
+            order: SortOrder::Ascending,
+            folders_first: true
+        }
+    }
+
+    /// sorts by the column, clicking the column that is already sorted flips the order
+    pub fn toggle(&mut self, column: Column) {
+        if self.column == column {
+            self.order = match self.order {
+                SortOrder::Ascending => SortOrder::Descending,
+                SortOrder::Descending => SortOrder::Ascending,
+            };
+        }
+        else {
+            self.column = column;
+            self.order = SortOrder::Ascending;
+        }
+    }
+
+    pub fn sort(&self, items: &mut [FileListItem]) {
+        items.sort_by(|a, b| {
+            if self.folders_first {
+                let folders = b.is_dir().cmp(&a.is_dir());
+                if folders != Ordering::Equal {
+                    return folders;
+                }
+            }
+            let ordering = compare(self.column, a, b).then_with(|| natural_cmp(&a.name(), &b.name()));
+            match self.order {
+                SortOrder::Ascending => ordering,
+                SortOrder::Descending => ordering.reverse(),
+            }
+        });
+    }
+}
+
+fn compare(column: Column, a: &FileListItem, b: &FileListItem) -> Ordering {
+    let (Some(a_metadata), Some(b_metadata)) = (&a.metadata, &b.metadata) else {
+        return a.metadata.is_some().cmp(&b.metadata.is_some());
+    };
+    match column {
+        Column::Name => Ordering::Equal,
+        Column::Size => a_metadata.size.cmp(&b_metadata.size),
+        Column::Type => natural_cmp(&a_metadata.type_name(&a.path), &b_metadata.type_name(&b.path)),
+        Column::Modified => a_metadata.modified.cmp(&b_metadata.modified),
+        Column::Created => a_metadata.created.cmp(&b_metadata.created),
+        Column::Accessed => a_metadata.accessed.cmp(&b_metadata.accessed),
+        Column::Owner => a_metadata.owner().cmp(&b_metadata.owner()),
+        Column::Group => a_metadata.group().cmp(&b_metadata.group()),
+        Column::Permissions => (a_metadata.mode & 0o7777).cmp(&(b_metadata.mode & 0o7777)),
+    }
+}
+
+/// compares names the way people read them, "file 2" comes before "file 10" and case is ignored
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a_char), Some(b_char)) if a_char.is_ascii_digit() && b_char.is_ascii_digit() => {
+                let a_number = take_number(&mut a_chars);
+                let b_number = take_number(&mut b_chars);
+                let ordering = a_number.len().cmp(&b_number.len()).then_with(|| a_number.cmp(&b_number));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            },
+            (Some(a_char), Some(b_char)) => {
+                let ordering = a_char.to_lowercase().cmp(b_char.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+/// the digits of a number without its leading zeros
+fn take_number(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut number = String::new();
+    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+        if !(number.is_empty() && digit == '0') {
+            number.push(digit);
+        }
+    }
+    number
+}
+
+/// the column headers, clicking one sorts by it, dragging its right edge resizes it and
+/// the context menu hides and shows columns. returns true when the sorting changed
+pub fn header(ui: &mut egui::Ui, columns: &mut Columns, sorting: &mut Sorting, width: f32) -> bool {
+    let height = ui.spacing().interact_size.y;
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(width, height), egui::Sense::click());
+    let layout = columns.layout(rect.left());
+    let mut changed = false;
+
+    if ui.is_rect_visible(rect) {
+        let painter = ui.painter().with_clip_rect(rect);
+        painter.rect_filled(rect, egui::Rounding::none(), ui.visuals().faint_bg_color);
+        let font = egui::TextStyle::Body.resolve(ui.style());
+        let color = ui.visuals().strong_text_color();
+        for (column, range) in &layout {
+            let cell = egui::Rect::from_x_y_ranges(*range, rect.y_range());
+            let cell_painter = painter.with_clip_rect(cell.shrink2(egui::vec2(CELL_PADDING, 0.0)));
+            cell_painter.text(egui::pos2(cell.left() + CELL_PADDING, cell.center().y), egui::Align2::LEFT_CENTER, column.title(), font.clone(), color);
+            if *column == sorting.column {
+                sort_indicator(&cell_painter, cell, sorting.order, color);
+            }
+            painter.vline(cell.right(), cell.y_range(), ui.visuals().widgets.noninteractive.bg_stroke);
+        }
+    }
+
+    if response.clicked() {
+        let pointer = response.interact_pointer_pos();
+        let clicked = layout.iter().find(|(_, range)| pointer.map(|pointer| range.contains(pointer.x)).unwrap_or(false));
+        if let Some((column, _)) = clicked {
+            sorting.toggle(*column);
+            changed = true;
+        }
+    }
+
+    for (column, range) in &layout {
+        let handle = egui::Rect::from_x_y_ranges(
+            Rangef::new(range.max - RESIZE_HANDLE_WIDTH / 2.0, range.max + RESIZE_HANDLE_WIDTH / 2.0),
+            rect.y_range()
+        );
+        let handle_response = ui.interact(handle, ui.id().with("column_resize").with(column.index()), egui::Sense::drag());
+        if handle_response.hovered() || handle_response.dragged() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeColumn);
+        }
+        if handle_response.dragged() {
+            let width = &mut columns.widths[column.index()];
+            *width = (*width + handle_response.drag_delta().x).max(MIN_COLUMN_WIDTH);
+        }
+    }
+
+    response.context_menu(|ui| {
+        for column in Column::ALL.iter().skip(1) {
+            let visible = &mut columns.visible[column.index()];
+            if ui.checkbox(visible, column.title()).changed() && !*visible && sorting.column == *column {
+                // the list can't stay sorted by a column nobody sees
+                *sorting = Sorting { folders_first: sorting.folders_first, ..Sorting::new() };
+                changed = true;
+            }
+        }
+        ui.separator();
+        if ui.checkbox(&mut sorting.folders_first, "folders first").changed() {
+            changed = true;
+        }
+    });
+    changed
+}
+
+fn sort_indicator(painter: &egui::Painter, cell: egui::Rect, order: SortOrder, color: egui::Color32) {
+    let center = egui::pos2(cell.right() - CELL_PADDING * 3.0, cell.center().y);
+    let size = 4.0;
+    let points = match order {
+        SortOrder::Ascending => vec![center + egui::vec2(-size, size / 2.0), center + egui::vec2(size, size / 2.0), center + egui::vec2(0.0, -size / 2.0)],
+        SortOrder::Descending => vec![center + egui::vec2(-size, -size / 2.0), center + egui::vec2(size, -size / 2.0), center + egui::vec2(0.0, size / 2.0)],
+    };
+    painter.add(egui::Shape::convex_polygon(points, color, egui::Stroke::NONE));
+}
+
+/// paints the cells of every visible column except the name in a row
+pub fn paint_cells(ui: &egui::Ui, rect: egui::Rect, columns: &Columns, item: &FileListItem) {
+    if !ui.is_rect_visible(rect) {
+        return;
+    }
+    let font = egui::TextStyle::Body.resolve(ui.style());
+    let color = if item.is_hidden() { ui.visuals().weak_text_color() } else { ui.visuals().text_color() };
+    for (column, range) in columns.layout(rect.left()) {
+        if column == Column::Name {
+            continue;
+        }
+        let cell = egui::Rect::from_x_y_ranges(range, rect.y_range()).shrink2(egui::vec2(CELL_PADDING, 0.0));
+        let painter = ui.painter().with_clip_rect(cell.intersect(ui.clip_rect()));
+        let (position, align) = if column.right_aligned() {
+            (egui::pos2(cell.right(), cell.center().y), egui::Align2::RIGHT_CENTER)
+        }
+        else {
+            (egui::pos2(cell.left(), cell.center().y), egui::Align2::LEFT_CENTER)
+        };
+        painter.text(position, align, column.text(item), font.clone(), color);
+    }
+}
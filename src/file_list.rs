@@ -1,77 +1,294 @@
 use std::path::PathBuf;
 
+use egui::emath::Rangef;
+use serde::{Deserialize, Serialize};
+
 use crate::{widgets, gui_extension::UiHelpersExt};
+use crate::details::{self, Columns, Sorting};
 use crate::icon_manager::IconManager;
+use crate::ignore;
+use crate::metadata::ItemMetadata;
+use crate::quick_filter;
+use crate::thumbnails;
 
 pub struct NewItem {
     pub kind: ItemKind,
     pub name: String
 }
 
+#[derive(Clone)]
 pub enum ItemKind {
     File,
     Directory
 }
 
-impl ItemKind {
-    pub fn from_path(path: &PathBuf) -> Self {
-        if path.is_dir() {
-            Self::Directory
-        }
-        else {
-            Self::File
-        }
-    }
-}
-
 pub enum FileListAction {
     Open(PathBuf),
+    OpenInNewTab(PathBuf),
     Create(NewItem),
-    Delete(PathBuf),
+    Trash,
+    Delete,
     Rename(PathBuf, String),
-    Select(usize),
-    Deselect(usize)
+    RenameSelection,
+    Copy,
+    Cut,
+    Paste,
+    Bookmark(PathBuf),
+    Select(usize, egui::Modifiers),
+    ContextSelect(usize),
+    SelectMany(Vec<usize>, bool),
+    SelectAll,
+    InvertSelection,
+    DeselectAll,
+    Sort
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViewMode {
+    List,
+    Details,
+    Grid
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IconSize {
+    Small,
+    Medium,
+    Large,
+    ExtraLarge
+}
+
+impl IconSize {
+    pub const ALL: [IconSize; 4] = [IconSize::Small, IconSize::Medium, IconSize::Large, IconSize::ExtraLarge];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            IconSize::Small => "small",
+            IconSize::Medium => "medium",
+            IconSize::Large => "large",
+            IconSize::ExtraLarge => "extra large",
+        }
+    }
+
+    pub fn pixels(self) -> f32 {
+        match self {
+            IconSize::Small => 32.0,
+            IconSize::Medium => 48.0,
+            IconSize::Large => 96.0,
+            IconSize::ExtraLarge => 128.0,
+        }
+    }
 }
 
 pub struct FileListItem {
     pub path: PathBuf,
-    pub selected: bool
+    pub selected: bool,
+    pub metadata: Option<ItemMetadata>,
+    /// false when the quick filter hides the item
+    pub visible: bool,
+    /// the characters of the name the quick filter matched
+    pub matched: Vec<usize>
 }
 
 impl FileListItem {
     pub fn new(path: PathBuf) -> Self {
-        Self { path: path, selected: false }
+        let metadata = ItemMetadata::read(&path);
+        Self { path: path, selected: false, metadata, visible: true, matched: Vec::new() }
+    }
+
+    pub fn refresh_metadata(&mut self) {
+        self.metadata = ItemMetadata::read(&self.path);
+    }
+
+    pub fn name(&self) -> String {
+        self.path.file_name().unwrap_or_else(|| std::ffi::OsStr::new("unknown")).to_string_lossy().to_string()
+    }
+
+    /// dotfiles, they are dimmed when they are shown
+    pub fn is_hidden(&self) -> bool {
+        ignore::is_hidden(&self.name())
     }
+
+    pub fn is_dir(&self) -> bool {
+        self.metadata.as_ref().map(|metadata| metadata.is_dir).unwrap_or(false)
+    }
+
+    pub fn kind(&self) -> ItemKind {
+        if self.is_dir() {
+            ItemKind::Directory
+        }
+        else {
+            ItemKind::File
+        }
+    }
+}
+
+/// a drag rectangle selection, the origin is in the coordinates of the scrolled content
+struct RubberBand {
+    origin: egui::Pos2,
+    additive: bool
+}
+
+/// where the rows and cells of the last frame were, used to hit test the rubber band
+struct Layout {
+    content_top: f32,
+    content_left: f32,
+    row_pitch: f32,
+    cell_pitch: f32,
+    items_per_row: usize,
+    item_count: usize
 }
 
 pub struct FileListWidget {
+    pub view_mode: ViewMode,
+    pub icon_size: IconSize,
+    columns: Columns,
+    sorting: Sorting,
     new_item: Option<NewItem>,
-    rename_request: bool
+    rename_request: bool,
+    rubber_band: Option<RubberBand>,
+    items_per_row: usize,
+    rows_per_page: usize,
+    scroll_to: Option<usize>,
+    viewport: Rangef
 }
+/// the item dragged out of a listing, the sidebar takes folders that are dropped on it
+pub fn dragged_path(ctx: &egui::Context) -> Option<PathBuf> {
+    ctx.data(|data| data.get_temp(dragged_path_id()))
+}
+
+pub fn take_dragged_path(ctx: &egui::Context) -> Option<PathBuf> {
+    let path = dragged_path(ctx);
+    ctx.data_mut(|data| data.remove::<PathBuf>(dragged_path_id()));
+    path
+}
+
+fn dragged_path_id() -> egui::Id {
+    egui::Id::new("dragged_path")
+}
+
 const FILE_ITEM_PADDING: f32 = 4.0;
+const LIST_ICON_SIZE: i32 = 16;
+const GRID_LABEL_LINES: f32 = 2.0;
 impl FileListWidget {
     pub fn new() -> Self {
         Self {
+            view_mode: ViewMode::List,
+            icon_size: IconSize::Medium,
+            columns: Columns::new(),
+            sorting: Sorting::new(),
             new_item: None,
-            rename_request: false
+            rename_request: false,
+            rubber_band: None,
+            items_per_row: 1,
+            rows_per_page: 1,
+            scroll_to: None,
+            viewport: Rangef::new(0.0, 0.0)
         }
     }
 
+    pub fn sort_items(&self, items: &mut [FileListItem]) {
+        self.sorting.sort(items);
+    }
+
+    pub fn sorting(&self) -> Sorting {
+        self.sorting
+    }
+
+    pub fn set_sorting(&mut self, sorting: Sorting) {
+        self.sorting = sorting;
+    }
+
     pub fn new_item(&mut self, item_kind: ItemKind) {
         self.new_item = Some(NewItem { kind: item_kind, name: String::new() });
     }
 
-    pub fn show(&mut self, ui: &mut egui::Ui, items: &Vec<FileListItem>, icons: &mut IconManager) -> Vec<FileListAction> {
+    /// how many items the last frame fit on a row, arrow keys move up and down by that much
+    pub fn items_per_row(&self) -> usize {
+        self.items_per_row
+    }
+
+    /// how many items fit in the listing in the last frame, page up and down move by that much
+    pub fn items_per_page(&self) -> usize {
+        self.rows_per_page * self.items_per_row
+    }
+
+    /// starts renaming the selected item in the next frame
+    pub fn rename(&mut self) {
+        self.rename_request = true;
+    }
+
+    /// scrolls the next frame just enough for the item to be visible, if it isn't filtered out
+    pub fn scroll_to(&mut self, index: usize) {
+        self.scroll_to = Some(index);
+    }
+
+    /// `focus` is the item the keyboard moves from, it gets an outline
+    pub fn show(&mut self, ui: &mut egui::Ui, items: &Vec<FileListItem>, focus: Option<usize>, icons: &mut IconManager) -> Vec<FileListAction> {
         let mut actions= Vec::new();
         let width = ui.available_width();
-        let height = self.file_item_total_height(ui);
-        let total_rows = items.len() + if self.new_item.is_some() {1} else {0};
         let renaming = self.rename_request;
         if self.rename_request {
             self.rename_request = false;
         }
 
-        egui::ScrollArea::vertical().show_rows(ui, height, total_rows, |ui, mut row_range| {
+        if let Some(path) = dragged_path(ui.ctx()) {
+            if ui.input(|input| input.pointer.primary_down()) {
+                let name = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().to_string();
+                egui::show_tooltip_at_pointer(ui.ctx(), dragged_path_id(), |ui| ui.label(name));
+            }
+            else {
+                take_dragged_path(ui.ctx());
+            }
+        }
+
+        let selected_count = items.iter().filter(|item| item.selected).count();
+        // the rows and cells are laid out for the items the quick filter shows, these are their indices
+        let visible: Vec<usize> = (0..items.len()).filter(|index| items[*index].visible).collect();
+        let modifiers = ui.input(|input| input.modifiers);
+
+        if self.view_mode == ViewMode::Details && details::header(ui, &mut self.columns, &mut self.sorting, width) {
+            actions.push(FileListAction::Sort);
+        }
+
+        let (height, cell_pitch) = match self.view_mode {
+            ViewMode::List | ViewMode::Details => (self.file_item_total_height(ui), width),
+            ViewMode::Grid => {
+                let cell = self.grid_cell_size(ui);
+                (cell.y, cell.x + ui.spacing().item_spacing.x)
+            }
+        };
+        let items_per_row = match self.view_mode {
+            ViewMode::List | ViewMode::Details => 1,
+            ViewMode::Grid => (((width + ui.spacing().item_spacing.x) / cell_pitch) as usize).max(1)
+        };
+        self.items_per_row = items_per_row;
+        let row_pitch = height + ui.spacing().item_spacing.y;
+        let creating = self.new_item.is_some();
+        let total_rows = visible.len().div_ceil(items_per_row) + if creating {1} else {0};
+
+        let mut scroll_area = egui::ScrollArea::vertical().auto_shrink([false, false]);
+        if let Some(position) = self.scroll_to.take().and_then(|index| visible.iter().position(|visible| *visible == index)) {
+            let top = (position / items_per_row) as f32 * row_pitch;
+            if top < self.viewport.min {
+                scroll_area = scroll_area.vertical_scroll_offset(top);
+            }
+            else if top + height > self.viewport.max {
+                scroll_area = scroll_area.vertical_scroll_offset(top + height - self.viewport.span());
+            }
+        }
+
+        let output = scroll_area.show_rows(ui, height, total_rows, |ui, mut row_range| {
+            let layout = Layout {
+                content_top: ui.max_rect().top() - row_range.start as f32 * row_pitch,
+                content_left: ui.max_rect().left(),
+                row_pitch,
+                cell_pitch,
+                items_per_row,
+                item_count: visible.len()
+            };
             if let Some(mut new_item) = self.new_item.take() {
                 row_range.end -= 1;
                 let item = self.temp_file_item(ui, &mut new_item, width, icons);
@@ -84,46 +301,182 @@ impl FileListWidget {
                     self.new_item = Some(new_item);
                 }
             }
-            
-            for index in row_range {
-                let item = &items[index];
-                let item_response = self.file_item(ui, item, width, renaming, icons);
-                
-                let rect = item_response.response.rect;
-                if item_response.response.double_clicked() {
-                    actions.push(FileListAction::Open(item.path.clone()));
+
+            for row in row_range {
+                let indices = visible[row * items_per_row..((row + 1) * items_per_row).min(visible.len())].iter().copied();
+                let responses = match self.view_mode {
+                    ViewMode::List | ViewMode::Details => {
+                        indices.map(|index| (index, self.file_item(ui, &items[index], width, renaming, icons))).collect()
+                    },
+                    ViewMode::Grid => {
+                        ui.horizontal(|ui| {
+                            indices.map(|index| (index, self.grid_item(ui, &items[index], renaming, icons))).collect::<Vec<_>>()
+                        }).inner
+                    }
+                };
+                for (index, item_response) in responses {
+                    if focus == Some(index) {
+                        ui.painter().rect_stroke(item_response.response.rect.shrink(1.0), egui::Rounding::none(), ui.visuals().selection.stroke);
+                    }
+                    self.handle_item_response(ui, &items[index], index, item_response, &layout, selected_count, modifiers, &mut actions);
                 }
-                else if ui.pointer_pressed_at(rect) && ui.is_enabled() {
-                    actions.push(FileListAction::Select(index));
+            }
+
+            // the empty space under the items gets its own context menu
+            let empty_space = ui.available_rect_before_wrap();
+            if empty_space.height() > 0.0 {
+                let response = ui.allocate_rect(empty_space, egui::Sense::click_and_drag());
+                if ui.pointer_pressed_at(empty_space) && ui.is_enabled() && !modifiers.command && !modifiers.shift {
+                    actions.push(FileListAction::DeselectAll);
                 }
-                if let Some(text) = item_response.inner {
-                    actions.push(FileListAction::Rename(item.path.clone(), text));
+                if response.drag_started() && !creating {
+                    if let Some(origin) = ui.input(|input| input.pointer.press_origin()) {
+                        self.start_rubber_band(origin, &layout, modifiers);
+                    }
                 }
-                
-                let pressed_outside = ui.pointer_pressed_outside_of(rect);
-                let mut context_menu_clicked = false;
-                item_response.response.context_menu(|ui| {
-                    if ui.button("delete").clicked() {
-                        actions.push(FileListAction::Delete(item.path.clone()));
+                response.context_menu(|ui| {
+                    if ui.button("paste").clicked() {
+                        actions.push(FileListAction::Paste);
                         ui.close_menu();
                     }
-                    if ui.button("rename").clicked() {
-                        self.rename_request = true;
+                    ui.separator();
+                    if ui.button("select all").clicked() {
+                        actions.push(FileListAction::SelectAll);
+                        ui.close_menu();
+                    }
+                    if ui.button("invert selection").clicked() {
+                        actions.push(FileListAction::InvertSelection);
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button("new folder").clicked() {
+                        self.new_item(ItemKind::Directory);
+                        ui.close_menu();
+                    }
+                    if ui.button("new file").clicked() {
+                        self.new_item(ItemKind::File);
                         ui.close_menu();
                     }
-                    context_menu_clicked = ui.pointer_pressed_at(ui.max_rect());
                 });
-                if item.selected && pressed_outside && !context_menu_clicked && ui.is_enabled() {
-                    actions.push(FileListAction::Deselect(index));
-                }
             }
-        }).inner_rect;  
+
+            if let Some(action) = self.update_rubber_band(ui, &layout, &visible) {
+                actions.push(action);
+            }
+        });
+        self.viewport = Rangef::new(output.state.offset.y, output.state.offset.y + output.inner_rect.height());
+        self.rows_per_page = ((output.inner_rect.height() / row_pitch) as usize).max(1);
         actions
     }
 
-    fn file_item(&mut self, ui: &mut egui::Ui, item: &FileListItem, width: f32, renaming: bool, icons: &mut IconManager) -> egui::InnerResponse<Option<String>> {
+    #[allow(clippy::too_many_arguments)]
+    fn handle_item_response(&mut self, ui: &egui::Ui, item: &FileListItem, index: usize, item_response: egui::InnerResponse<(Option<String>, egui::Rect)>, layout: &Layout, selected_count: usize, modifiers: egui::Modifiers, actions: &mut Vec<FileListAction>) {
+        let (renamed, content_rect) = item_response.inner;
+        let response = item_response.response;
+
+        let rect = response.rect;
+        if response.double_clicked() {
+            actions.push(FileListAction::Open(item.path.clone()));
+        }
+        else if response.middle_clicked() && item.is_dir() {
+            actions.push(FileListAction::OpenInNewTab(item.path.clone()));
+        }
+        else if ui.pointer_pressed_at(rect) && ui.is_enabled() {
+            if ui.input(|input| input.pointer.primary_pressed()) {
+                actions.push(FileListAction::Select(index, modifiers));
+            }
+            else if ui.input(|input| input.pointer.secondary_pressed()) {
+                actions.push(FileListAction::ContextSelect(index));
+            }
+        }
+        if let Some(text) = renamed {
+            actions.push(FileListAction::Rename(item.path.clone(), text));
+        }
+        // dragging from the blank part of an item starts a rubber band, like from the empty space,
+        // dragging the item itself carries it to the sidebar
+        if response.drag_started() && self.new_item.is_none() {
+            match ui.input(|input| input.pointer.press_origin()) {
+                Some(origin) if !content_rect.contains(origin) => self.start_rubber_band(origin, layout, modifiers),
+                Some(_) => ui.ctx().data_mut(|data| data.insert_temp(dragged_path_id(), item.path.clone())),
+                None => {}
+            }
+        }
+
+        response.context_menu(|ui| {
+            if ui.button("copy").clicked() {
+                actions.push(FileListAction::Copy);
+                ui.close_menu();
+            }
+            if ui.button("cut").clicked() {
+                actions.push(FileListAction::Cut);
+                ui.close_menu();
+            }
+            if ui.button("paste").clicked() {
+                actions.push(FileListAction::Paste);
+                ui.close_menu();
+            }
+            if item.is_dir() && ui.button("add to bookmarks").clicked() {
+                actions.push(FileListAction::Bookmark(item.path.clone()));
+                ui.close_menu();
+            }
+            ui.separator();
+            if ui.button("move to trash").clicked() {
+                actions.push(FileListAction::Trash);
+                ui.close_menu();
+            }
+            if ui.button("delete permanently").clicked() {
+                actions.push(FileListAction::Delete);
+                ui.close_menu();
+            }
+            if ui.button("rename").clicked() {
+                if selected_count > 1 {
+                    actions.push(FileListAction::RenameSelection);
+                }
+                else {
+                    self.rename_request = true;
+                }
+                ui.close_menu();
+            }
+        });
+    }
+
+    fn start_rubber_band(&mut self, origin: egui::Pos2, layout: &Layout, modifiers: egui::Modifiers) {
+        self.rubber_band = Some(RubberBand {
+            origin: egui::pos2(origin.x, origin.y - layout.content_top),
+            additive: modifiers.command || modifiers.shift
+        });
+    }
+
+    /// paints the rubber band and selects the items it covers, `visible` maps the cells to the items shown in them
+    fn update_rubber_band(&mut self, ui: &mut egui::Ui, layout: &Layout, visible: &[usize]) -> Option<FileListAction> {
+        let band = self.rubber_band.as_ref()?;
+        if !ui.input(|input| input.pointer.primary_down()) {
+            self.rubber_band = None;
+            return None;
+        }
+        let pointer = ui.input(|input| input.pointer.interact_pos())?;
+        let origin = egui::pos2(band.origin.x, band.origin.y + layout.content_top);
+        let rect = egui::Rect::from_two_pos(origin, pointer);
+        let selection = ui.visuals().selection;
+        ui.painter().rect(rect, egui::Rounding::none(), selection.bg_fill.linear_multiply(0.3), selection.stroke);
+
+        let first_row = ((rect.top() - layout.content_top).max(0.0) / layout.row_pitch) as usize;
+        let last_row = ((rect.bottom() - layout.content_top).max(0.0) / layout.row_pitch) as usize;
+        let last_column = layout.items_per_row - 1;
+        let first_column = (((rect.left() - layout.content_left).max(0.0) / layout.cell_pitch) as usize).min(last_column);
+        let last_column = (((rect.right() - layout.content_left).max(0.0) / layout.cell_pitch) as usize).min(last_column);
+        let indices = (first_row..=last_row)
+            .flat_map(|row| (first_column..=last_column).map(move |column| row * layout.items_per_row + column))
+            .filter(|position| *position < layout.item_count)
+            .map(|position| visible[position])
+            .collect();
+        Some(FileListAction::SelectMany(indices, band.additive))
+    }
+
+    /// returns the new name when the item was renamed and the rect of its icon and label
+    fn file_item(&mut self, ui: &mut egui::Ui, item: &FileListItem, width: f32, renaming: bool, icons: &mut IconManager) -> egui::InnerResponse<(Option<String>, egui::Rect)> {
         let size = egui::vec2(width, self.file_item_total_height(ui));
-        let name =  item.path.file_name().unwrap_or_else(|| std::ffi::OsStr::new("unknown")).to_string_lossy().to_string();
+        let name = item.name();
         let response = ui.custom_widget(size, |ui, rect, response| {
             response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, &name));
             if ui.is_rect_visible(rect) {
@@ -133,22 +486,105 @@ impl FileListWidget {
                 }
             }
 
-            let text = ui.horizontal_centered(|ui| {
+            // in the details view the name only gets its own column and the rest of the row shows the metadata
+            let name_rect = match self.view_mode {
+                ViewMode::List | ViewMode::Grid => rect,
+                ViewMode::Details => {
+                    details::paint_cells(ui, rect, &self.columns, item);
+                    egui::Rect::from_min_size(rect.min, egui::vec2(self.columns.name_width(), rect.height()))
+                }
+            };
+            let mut name_ui = ui.child_ui(name_rect, egui::Layout::left_to_right(egui::Align::Center));
+            name_ui.set_clip_rect(name_rect.intersect(ui.clip_rect()));
+            if item.is_hidden() {
+                name_ui.visuals_mut().override_text_color = Some(ui.visuals().weak_text_color());
+            }
+            let label = name_ui.horizontal_centered(|ui| {
                 ui.spacing_mut().item_spacing = egui::vec2(FILE_ITEM_PADDING, 0.0);
                 ui.add_space(FILE_ITEM_PADDING);
                 let ctx = ui.ctx().clone();
                 let mut label = widgets::RenamableLabel::new(name, &ctx);
-                self.add_icon(ui, &ItemKind::from_path(&item.path), label.get_text(), icons);
+                label.highlight(&item.matched);
+                let icon_size = self.file_item_height(ui);
+                self.add_icon(ui, &item.kind(), label.get_text(), icon_size, icons);
                 if renaming && item.selected {
                     label.rename();
                 }
-                label.show(ui).inner
+                label.show(ui)
             }).inner;
-            text
+            let content_rect = egui::Rect::from_min_max(rect.min, egui::pos2(label.response.rect.right(), rect.bottom()));
+            (label.inner, content_rect)
         });
         response
     }
 
+    /// an item of the grid view, the icon on top of its name
+    fn grid_item(&mut self, ui: &mut egui::Ui, item: &FileListItem, renaming: bool, icons: &mut IconManager) -> egui::InnerResponse<(Option<String>, egui::Rect)> {
+        let (rect, response) = ui.allocate_exact_size(self.grid_cell_size(ui), egui::Sense::click_and_drag());
+        let name = item.name();
+        response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, &name));
+        if !ui.is_rect_visible(rect) {
+            return egui::InnerResponse::new((None, rect), response);
+        }
+        let visuals = ui.style().interact_selectable(&response, item.selected);
+        if response.hovered() || item.selected {
+            ui.painter().rect(rect, visuals.rounding, visuals.bg_fill, egui::Stroke::NONE);
+        }
+
+        let icon_size = self.icon_size.pixels();
+        let icon_rect = egui::Rect::from_center_size(
+            egui::pos2(rect.center().x, rect.top() + FILE_ITEM_PADDING + icon_size / 2.0),
+            egui::vec2(icon_size, icon_size)
+        );
+        // images show their thumbnail and their file type icon until it is ready
+        let modified = item.metadata.as_ref()
+            .filter(|metadata| !metadata.is_dir && thumbnails::is_supported(&item.path))
+            .and_then(|metadata| metadata.modified);
+        let thumbnail = modified.and_then(|modified| icons.get_thumbnail(&item.path, modified, icon_size));
+        if let Some(thumbnail) = thumbnail {
+            let size = thumbnail.size_vec2() * (icon_size / thumbnail.size_vec2().max_elem()).min(1.0);
+            let mut thumbnail_ui = ui.child_ui(egui::Rect::from_center_size(icon_rect.center(), size), egui::Layout::left_to_right(egui::Align::Center));
+            thumbnail_ui.image_consider_disabled(thumbnail, size);
+        }
+        else {
+            let mut icon_ui = ui.child_ui(icon_rect, egui::Layout::left_to_right(egui::Align::Center));
+            self.add_icon(&mut icon_ui, &item.kind(), &name, icon_size, icons);
+        }
+
+        let label_area = egui::Rect::from_min_max(
+            egui::pos2(rect.left() + FILE_ITEM_PADDING, icon_rect.bottom() + FILE_ITEM_PADDING),
+            egui::pos2(rect.right() - FILE_ITEM_PADDING, rect.bottom() - FILE_ITEM_PADDING)
+        );
+        let ctx = ui.ctx().clone();
+        let mut label = widgets::RenamableLabel::new(name.clone(), &ctx);
+        if renaming && item.selected {
+            label.rename();
+        }
+        let (renamed, label_rect) = if label.is_renaming() {
+            let mut label_ui = ui.child_ui(label_area, egui::Layout::top_down(egui::Align::Center));
+            let label = label.show(&mut label_ui);
+            (label.inner, label.response.rect)
+        }
+        else {
+            // long names wrap and are cut after a couple of lines
+            let color = if item.is_hidden() { ui.visuals().weak_text_color() } else { visuals.text_color() };
+            let mut job = quick_filter::highlighted(&name, &item.matched, ui.style(), color);
+            job.wrap.max_width = label_area.width();
+            let galley = ui.fonts(|fonts| fonts.layout_job(job));
+            let position = egui::pos2(label_area.center().x - galley.size().x / 2.0, label_area.top());
+            let label_rect = egui::Rect::from_min_size(position, galley.size()).intersect(label_area);
+            ui.painter().with_clip_rect(label_area.intersect(ui.clip_rect())).galley(position, galley);
+            (None, label_rect)
+        };
+        egui::InnerResponse::new((renamed, icon_rect.union(label_rect)), response)
+    }
+
+    fn grid_cell_size(&self, ui: &egui::Ui) -> egui::Vec2 {
+        let icon_size = self.icon_size.pixels();
+        let label_height = ui.get_text_style_height(egui::TextStyle::Body) * GRID_LABEL_LINES;
+        egui::vec2((icon_size * 1.5).max(96.0), icon_size + label_height + FILE_ITEM_PADDING * 3.0)
+    }
+
     fn temp_file_item(&mut self, ui: &mut egui::Ui, item: &mut NewItem, width: f32, icons: &mut IconManager) -> egui::InnerResponse<bool> {
         let size = egui::vec2(width, self.file_item_total_height(ui));
         let response = ui.push_id(egui::Id::new("temp_file_item"), |ui| {
@@ -158,7 +594,8 @@ impl FileListWidget {
             }
             ui.allocate_ui_with_layout(size, egui::Layout::left_to_right(egui::Align::Center), |ui| {
                 ui.add_space(4.0);
-                self.add_icon(ui, &item.kind, &item.name, icons);
+                let icon_size = self.file_item_height(ui);
+                self.add_icon(ui, &item.kind, &item.name, icon_size, icons);
                 let respnonse = ui.text_edit_singleline(&mut item.name);
                 respnonse.lost_focus()
             }).inner
@@ -166,12 +603,13 @@ impl FileListWidget {
         response
     }
 
-    fn add_icon(&mut self, ui: &mut egui::Ui, kind: &ItemKind, name: &str, icons: &mut IconManager) {
-        let height = self.file_item_height(ui);
-        let icon_size = egui::vec2(height, height);
+    fn add_icon(&mut self, ui: &mut egui::Ui, kind: &ItemKind, name: &str, size: f32, icons: &mut IconManager) {
+        let icon_size = egui::vec2(size, size);
+        // the small list icons are enough up to twice their size, past that a bigger one is loaded
+        let pixels = if size <= LIST_ICON_SIZE as f32 * 2.0 { LIST_ICON_SIZE } else { size as i32 };
         match kind {
             ItemKind::File => {
-                if let Some(icon) = icons.get_icon(&name) {
+                if let Some(icon) = icons.get_icon(name, pixels) {
                     ui.image_consider_disabled(icon, icon_size);
                 }
             },
@@ -0,0 +1,177 @@
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use zbus::blocking::{Connection, ConnectionBuilder};
+use zbus::dbus_interface;
+
+use crate::instance::Request;
+use crate::trash;
+
+const BUS_NAME: &str = "org.freedesktop.FileManager1";
+const OBJECT_PATH: &str = "/org/freedesktop/FileManager1";
+
+/// what browsers, editors and the like call to show a file or folder in the file manager,
+/// the calls become the same requests the single instance mode hands over
+struct FileManager {
+    sender: Sender<Request>,
+    context: egui::Context
+}
+
+impl FileManager {
+    fn send(&self, requests: impl IntoIterator<Item = Request>) {
+        for request in requests {
+            let _ = self.sender.send(request);
+        }
+        let _ = self.sender.send(Request::Focus);
+        self.context.request_repaint();
+    }
+}
+
+#[dbus_interface(name = "org.freedesktop.FileManager1")]
+impl FileManager {
+    fn show_folders(&self, uris: Vec<String>, _startup_id: &str) -> zbus::fdo::Result<()> {
+        let paths = paths_from_uris(&uris)?;
+        self.send(paths.into_iter().map(Request::Open));
+        Ok(())
+    }
+
+    fn show_items(&self, uris: Vec<String>, _startup_id: &str) -> zbus::fdo::Result<()> {
+        let paths = paths_from_uris(&uris)?;
+        self.send(group_by_folder(paths).into_iter().map(Request::Reveal));
+        Ok(())
+    }
+
+    fn show_item_properties(&self, uris: Vec<String>, _startup_id: &str) -> zbus::fdo::Result<()> {
+        let paths = paths_from_uris(&uris)?;
+        self.send(group_by_folder(paths).into_iter().map(Request::Properties));
+        Ok(())
+    }
+}
+
+/// all uris are checked before anything is opened so a bad one doesn't leave half of them open
+fn paths_from_uris(uris: &[String]) -> zbus::fdo::Result<Vec<PathBuf>> {
+    uris.iter().map(|uri| path_from_uri(uri)).collect::<Result<Vec<PathBuf>, String>>()
+        .map_err(zbus::fdo::Error::InvalidArgs)
+}
+
+/// the paths of each folder in the order the folders came first
+fn group_by_folder(paths: Vec<PathBuf>) -> Vec<Vec<PathBuf>> {
+    let mut groups: Vec<Vec<PathBuf>> = Vec::new();
+    for path in paths {
+        match groups.iter_mut().find(|group| group[0].parent() == path.parent()) {
+            Some(group) => group.push(path),
+            None => groups.push(vec![path])
+        }
+    }
+    groups
+}
+
+/// the local path of a `file://` uri that exists
+fn path_from_uri(uri: &str) -> Result<PathBuf, String> {
+    let Some(path) = trash::path_from_uri(uri) else {
+        return Err(format!("{uri} is not a file:// uri"));
+    };
+    if path.exists() {
+        Ok(path)
+    }
+    else {
+        Err(format!("{} doesn't exist", path.display()))
+    }
+}
+
+/// owns the name on the session bus as long as it is kept
+pub struct FileManagerService {
+    _connection: Connection,
+    requests: Receiver<Request>
+}
+
+impl FileManagerService {
+    pub fn start(ctx: &egui::Context) -> Result<Self, String> {
+        Self::start_on(ConnectionBuilder::session(), ctx)
+    }
+
+    fn start_on(bus: zbus::Result<ConnectionBuilder>, ctx: &egui::Context) -> Result<Self, String> {
+        let (sender, requests) = mpsc::channel();
+        let file_manager = FileManager { sender, context: ctx.clone() };
+        let connection = bus
+            .and_then(|builder| builder.name(BUS_NAME))
+            .and_then(|builder| builder.serve_at(OBJECT_PATH, file_manager))
+            .and_then(|builder| builder.build())
+            .map_err(|error| format!("Couldn't register as the file manager on D-Bus: {error}"))?;
+        Ok(Self { _connection: connection, requests })
+    }
+
+    pub fn take_requests(&self) -> Vec<Request> {
+        self.requests.try_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::{BufRead, BufReader};
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+
+    use super::*;
+
+    fn uri(path: &Path) -> String {
+        format!("file://{}", trash::encode_path(path))
+    }
+
+    /// runs the service on a bus of its own, skipped where there is no dbus-daemon
+    #[test]
+    fn calls_are_turned_into_requests() {
+        let Ok(mut daemon) = Command::new("dbus-daemon").args(["--session", "--print-address", "--nofork"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn() else {
+            eprintln!("dbus-daemon isn't installed, skipping");
+            return;
+        };
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap()).read_line(&mut address).unwrap();
+        let address = address.trim();
+
+        let root = std::env::temp_dir().join(format!("file-explorer-dbus-test-{}", std::process::id()));
+        let (first, second) = (root.join("first"), root.join("second"));
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        let files = [first.join("a"), second.join("b"), first.join("c")];
+        for file in &files {
+            fs::write(file, "").unwrap();
+        }
+
+        let service = FileManagerService::start_on(ConnectionBuilder::address(address), &egui::Context::default()).unwrap();
+        let client = ConnectionBuilder::address(address).unwrap().build().unwrap();
+        let call = |method: &str, paths: &[&PathBuf]| {
+            let uris: Vec<String> = paths.iter().map(|path| uri(path)).collect();
+            client.call_method(Some(BUS_NAME), OBJECT_PATH, Some(BUS_NAME), method, &(uris, ""))
+        };
+
+        call("ShowFolders", &[&first, &second]).unwrap();
+        assert_eq!(service.take_requests(), [
+            Request::Open(first.clone()),
+            Request::Open(second.clone()),
+            Request::Focus
+        ]);
+
+        call("ShowItems", &[&files[0], &files[1], &files[2]]).unwrap();
+        assert_eq!(service.take_requests(), [
+            Request::Reveal(vec![files[0].clone(), files[2].clone()]),
+            Request::Reveal(vec![files[1].clone()]),
+            Request::Focus
+        ]);
+
+        call("ShowItemProperties", &[&files[1]]).unwrap();
+        assert_eq!(service.take_requests(), [Request::Properties(vec![files[1].clone()]), Request::Focus]);
+
+        // nothing is opened when one of the uris is bad
+        assert!(call("ShowItems", &[&files[0], &root.join("missing")]).is_err());
+        assert!(service.take_requests().is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = daemon.kill();
+        let _ = daemon.wait();
+    }
+}
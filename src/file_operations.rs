@@ -0,0 +1,215 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+const COPY_BUFFER_SIZE: usize = 256 * 1024;
+
+/// gets notified while an operation runs, returning an error from one of the methods aborts the operation
+pub trait OperationObserver {
+    fn on_file(&mut self, path: &Path) -> io::Result<()>;
+    fn on_bytes(&mut self, bytes: u64) -> io::Result<()>;
+    /// a whole tree was moved at once by renaming it
+    fn on_renamed(&mut self, files: u64, bytes: u64) -> io::Result<()>;
+}
+
+/// the amount of files and bytes an operation on `path` is going to process
+pub fn measure(path: &Path) -> io::Result<(u64, u64)> {
+    let metadata = fs::symlink_metadata(path)?;
+    if metadata.is_dir() {
+        let mut files = 1;
+        let mut bytes = 0;
+        for entry in fs::read_dir(path)? {
+            let (entry_files, entry_bytes) = measure(&entry?.path())?;
+            files += entry_files;
+            bytes += entry_bytes;
+        }
+        Ok((files, bytes))
+    }
+    else {
+        Ok((1, metadata.len()))
+    }
+}
+
+/// copies a file or a whole directory tree into `destination_dir`, keeping its name
+pub fn copy_into(source: &Path, destination_dir: &Path, observer: &mut impl OperationObserver) -> io::Result<PathBuf> {
+    let destination = destination_path(source, destination_dir)?;
+    copy_or_clean_up(source, &destination, observer)?;
+    Ok(destination)
+}
+
+/// copies into `destination_dir`, an item with the same name is kept by giving the copy a free name like "name (copy).txt"
+pub fn copy_into_with_free_name(source: &Path, destination_dir: &Path, observer: &mut impl OperationObserver) -> io::Result<PathBuf> {
+    if destination_dir.starts_with(source) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("cannot put {} inside of itself", source.display())));
+    }
+    let destination = free_destination_path(source, destination_dir)?;
+    copy_or_clean_up(source, &destination, observer)?;
+    Ok(destination)
+}
+
+/// moves a file or a directory into `destination_dir`,
+/// a rename is used when possible and a copy followed by a delete when the destination is on another filesystem
+pub fn move_into(source: &Path, destination_dir: &Path, observer: &mut impl OperationObserver) -> io::Result<PathBuf> {
+    let destination = destination_path(source, destination_dir)?;
+    move_to(source, &destination, observer)?;
+    Ok(destination)
+}
+
+/// moves `source` to exactly `destination` which must not exist yet
+pub fn move_to(source: &Path, destination: &Path, observer: &mut impl OperationObserver) -> io::Result<()> {
+    if fs::symlink_metadata(destination).is_ok() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} already exists", destination.display())));
+    }
+    match fs::rename(source, destination) {
+        Ok(()) => {
+            let (files, bytes) = measure(destination)?;
+            observer.on_renamed(files, bytes)
+        },
+        Err(error) if error.kind() == io::ErrorKind::CrossesDevices => {
+            copy_or_clean_up(source, destination, observer)?;
+            remove_recursive(source, &mut IgnoreProgress)
+        },
+        Err(error) => Err(error)
+    }
+}
+
+/// copies `source` to exactly `destination` which must not exist yet
+pub fn copy_to(source: &Path, destination: &Path, observer: &mut impl OperationObserver) -> io::Result<()> {
+    if fs::symlink_metadata(destination).is_ok() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} already exists", destination.display())));
+    }
+    copy_or_clean_up(source, destination, observer)
+}
+
+pub fn remove(path: &Path, observer: &mut impl OperationObserver) -> io::Result<()> {
+    remove_recursive(path, observer)
+}
+
+/// copies `source` to `destination` which must not exist yet,
+/// when the copy fails or gets cancelled everything that was already copied is removed again
+fn copy_or_clean_up(source: &Path, destination: &Path, observer: &mut impl OperationObserver) -> io::Result<()> {
+    let result = copy_recursive(source, destination, observer);
+    if result.is_err() && fs::symlink_metadata(destination).is_ok() {
+        let _ = remove_recursive(destination, &mut IgnoreProgress);
+    }
+    result
+}
+
+fn copy_recursive(source: &Path, destination: &Path, observer: &mut impl OperationObserver) -> io::Result<()> {
+    observer.on_file(source)?;
+    let metadata = fs::symlink_metadata(source)?;
+    if metadata.is_dir() {
+        fs::create_dir(destination)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &destination.join(entry.file_name()), observer)?;
+        }
+        fs::set_permissions(destination, metadata.permissions())?;
+    }
+    else if metadata.is_symlink() {
+        copy_symlink(source, destination)?;
+    }
+    else {
+        copy_file(source, destination, observer)?;
+        fs::set_permissions(destination, metadata.permissions())?;
+    }
+    Ok(())
+}
+
+/// a chunked copy so the observer can follow the progress and cancel in the middle of big files
+fn copy_file(source: &Path, destination: &Path, observer: &mut impl OperationObserver) -> io::Result<()> {
+    let mut reader = fs::File::open(source)?;
+    let mut writer = fs::OpenOptions::new().write(true).create_new(true).open(destination)?;
+    let mut buffer = vec![0; COPY_BUFFER_SIZE];
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error)
+        };
+        writer.write_all(&buffer[..read])?;
+        observer.on_bytes(read as u64)?;
+    }
+    writer.flush()
+}
+
+fn remove_recursive(path: &Path, observer: &mut impl OperationObserver) -> io::Result<()> {
+    observer.on_file(path)?;
+    let metadata = fs::symlink_metadata(path)?;
+    if metadata.is_dir() {
+        for entry in fs::read_dir(path)? {
+            remove_recursive(&entry?.path(), observer)?;
+        }
+        fs::remove_dir(path)
+    }
+    else {
+        fs::remove_file(path)?;
+        observer.on_bytes(metadata.len())
+    }
+}
+
+/// used for cleaning up, which should not be cancelled half way
+struct IgnoreProgress;
+
+impl OperationObserver for IgnoreProgress {
+    fn on_file(&mut self, _path: &Path) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn on_bytes(&mut self, _bytes: u64) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn on_renamed(&mut self, _files: u64, _bytes: u64) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn destination_path(source: &Path, destination_dir: &Path) -> io::Result<PathBuf> {
+    let name = source.file_name().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("{} has no name", source.display())))?;
+    if destination_dir.starts_with(source) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("cannot put {} inside of itself", source.display())));
+    }
+    let destination = destination_dir.join(name);
+    if destination.exists() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} already exists", destination.display())));
+    }
+    Ok(destination)
+}
+
+fn free_destination_path(source: &Path, destination_dir: &Path) -> io::Result<PathBuf> {
+    let name = source.file_name().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("{} has no name", source.display())))?;
+    let destination = destination_dir.join(name);
+    if !destination.exists() {
+        return Ok(destination);
+    }
+    let path = Path::new(name);
+    let (stem, extension) = match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(extension)) if !source.is_dir() => (stem.to_string_lossy(), format!(".{}", extension.to_string_lossy())),
+        _ => (name.to_string_lossy(), String::new())
+    };
+    for attempt in 1.. {
+        let name = if attempt == 1 {
+            format!("{stem} (copy){extension}")
+        }
+        else {
+            format!("{stem} (copy {attempt}){extension}")
+        };
+        let destination = destination_dir.join(name);
+        if !destination.exists() {
+            return Ok(destination);
+        }
+    }
+    unreachable!()
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, destination)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    fs::copy(source, destination).map(|_| ())
+}
@@ -11,7 +11,6 @@ pub trait UiHelpersExt {
     fn custom_widget<T>(&mut self, size: egui::Vec2, add_contents: impl FnOnce(&mut egui::Ui, egui::Rect, &mut egui::Response) -> T) -> InnerResponse<T>;
 
     fn pointer_pressed_at(&self, rect: egui::Rect) -> bool;
-    fn pointer_pressed_outside_of(&self, rect: egui::Rect) -> bool;
 }
 
 impl UiHelpersExt for egui::Ui {
@@ -59,14 +58,6 @@ impl UiHelpersExt for egui::Ui {
             false
         }
     }
-    fn pointer_pressed_outside_of(&self, rect: egui::Rect) -> bool {
-        if self.input(|input| input.pointer.any_pressed()) {
-            !self.rect_contains_pointer(rect)
-        }
-        else {
-            false
-        }
-    }
 }
 
 pub trait PainterPrimitiveExt {
@@ -81,11 +72,3 @@ impl PainterPrimitiveExt for egui::Painter {
         self.line_segment([bottom_left, tip], stroke);
     }
 }
-
-pub fn split_rect_horizontally(parent: egui::Rect, spacing: f32, padding: egui::Vec2, a_width: f32, b_width: f32) -> (egui::Rect, egui::Rect) {
-    let content_rect = parent.shrink2(padding);
-    let a = egui::Rect::from_min_size(content_rect.min, egui::vec2(a_width, content_rect.height()));
-    let b_x = content_rect.min.x + a_width + spacing;
-    let b = egui::Rect::from_min_size(egui::pos2(b_x, content_rect.min.y), egui::vec2(b_width, content_rect.height()));
-    return (a, b);
-}
\ No newline at end of file
@@ -0,0 +1,171 @@
+use egui::text::{LayoutJob, TextFormat};
+
+/// what the highlighter knows about a language, enough to color keywords, comments, strings and numbers
+struct Syntax {
+    keywords: &'static [&'static str],
+    line_comment: Option<&'static str>,
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char]
+}
+
+const RUST: Syntax = Syntax {
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn", "for",
+        "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static",
+        "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while"
+    ],
+    line_comment: Some("//"),
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"']
+};
+
+const C_LIKE: Syntax = Syntax {
+    keywords: &[
+        "abstract", "auto", "bool", "break", "case", "catch", "char", "class", "const", "continue", "default", "delete", "do",
+        "double", "else", "enum", "export", "extends", "extern", "false", "final", "float", "for", "func", "function", "go",
+        "goto", "if", "implements", "import", "include", "int", "interface", "let", "long", "namespace", "new", "null",
+        "nullptr", "package", "private", "protected", "public", "return", "short", "signed", "static", "struct", "switch",
+        "template", "this", "throw", "true", "try", "typedef", "typeof", "union", "unsigned", "using", "var", "virtual",
+        "void", "volatile", "while", "yield"
+    ],
+    line_comment: Some("//"),
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`']
+};
+
+const PYTHON: Syntax = Syntax {
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif", "else", "except", "False",
+        "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass",
+        "raise", "return", "True", "try", "while", "with", "yield"
+    ],
+    line_comment: Some("#"),
+    block_comment: None,
+    quotes: &['"', '\'']
+};
+
+const SHELL: Syntax = Syntax {
+    keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if", "in", "local", "return", "then",
+        "until", "while"
+    ],
+    line_comment: Some("#"),
+    block_comment: None,
+    quotes: &['"', '\'']
+};
+
+const CONFIG: Syntax = Syntax {
+    keywords: &["true", "false", "yes", "no", "null"],
+    line_comment: Some("#"),
+    block_comment: None,
+    quotes: &['"', '\'']
+};
+
+fn syntax_for(extension: &str) -> Option<&'static Syntax> {
+    match extension.to_ascii_lowercase().as_str() {
+        "rs" => Some(&RUST),
+        "c" | "h" | "cc" | "cpp" | "hpp" | "cs" | "java" | "js" | "jsx" | "ts" | "tsx" | "go" | "kt" | "swift" | "dart" => Some(&C_LIKE),
+        "py" | "pyw" => Some(&PYTHON),
+        "sh" | "bash" | "zsh" | "fish" => Some(&SHELL),
+        "toml" | "yaml" | "yml" | "ini" | "conf" | "cfg" => Some(&CONFIG),
+        _ => None
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Token {
+    Plain,
+    Keyword,
+    Comment,
+    String,
+    Number
+}
+
+/// lays out the text in a monospace font, colored by the language the extension belongs to
+pub fn highlight(text: &str, extension: &str, style: &egui::Style) -> LayoutJob {
+    let mut job = LayoutJob::default();
+    let font = egui::TextStyle::Monospace.resolve(style);
+    let color = |token: Token| match (token, style.visuals.dark_mode) {
+        (Token::Plain, _) => style.visuals.text_color(),
+        (Token::Keyword, true) => egui::Color32::from_rgb(240, 130, 110),
+        (Token::Keyword, false) => egui::Color32::from_rgb(200, 50, 50),
+        (Token::Comment, _) => egui::Color32::GRAY,
+        (Token::String, true) => egui::Color32::from_rgb(150, 200, 110),
+        (Token::String, false) => egui::Color32::from_rgb(40, 130, 40),
+        (Token::Number, true) => egui::Color32::from_rgb(110, 170, 240),
+        (Token::Number, false) => egui::Color32::from_rgb(30, 90, 200),
+    };
+    let mut append = |range: std::ops::Range<usize>, token: Token| {
+        if !range.is_empty() {
+            job.append(&text[range], 0.0, TextFormat::simple(font.clone(), color(token)));
+        }
+    };
+    let Some(syntax) = syntax_for(extension) else {
+        append(0..text.len(), Token::Plain);
+        return job;
+    };
+
+    // plain text is collected until the next token so the job doesn't get a section per character
+    let mut plain_start = 0;
+    let mut index = 0;
+    while index < text.len() {
+        let rest = &text[index..];
+        let Some(character) = rest.chars().next() else {
+            break;
+        };
+        let token = if syntax.line_comment.map(|start| rest.starts_with(start)).unwrap_or(false) {
+            Some((Token::Comment, rest.find('\n').unwrap_or(rest.len())))
+        }
+        else if let Some((start, end)) = syntax.block_comment.filter(|(start, _)| rest.starts_with(start)) {
+            let length = rest[start.len()..].find(end).map(|position| position + start.len() + end.len()).unwrap_or(rest.len());
+            Some((Token::Comment, length))
+        }
+        else if syntax.quotes.contains(&character) {
+            Some((Token::String, string_length(rest, character)))
+        }
+        else if character.is_ascii_digit() {
+            let length = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '_')).unwrap_or(rest.len());
+            Some((Token::Number, length))
+        }
+        else if character.is_alphabetic() || character == '_' {
+            let length = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            syntax.keywords.contains(&&rest[..length]).then_some((Token::Keyword, length)).or(Some((Token::Plain, length)))
+        }
+        else {
+            None
+        };
+
+        match token {
+            Some((Token::Plain, length)) => index += length,
+            Some((token, length)) => {
+                append(plain_start..index, Token::Plain);
+                append(index..index + length, token);
+                index += length;
+                plain_start = index;
+            },
+            None => index += character.len_utf8()
+        }
+    }
+    append(plain_start..text.len(), Token::Plain);
+    job
+}
+
+/// the length of a string literal including its quotes, a string that isn't closed ends with its line
+fn string_length(text: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (index, character) in text.char_indices().skip(1) {
+        if character == '\n' {
+            return index;
+        }
+        if escaped {
+            escaped = false;
+        }
+        else if character == '\\' {
+            escaped = true;
+        }
+        else if character == quote {
+            return index + character.len_utf8();
+        }
+    }
+    text.len()
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+pub struct History {
+    back: Vec<PathBuf>,
+    forward: Vec<PathBuf>
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            back: Vec::new(),
+            forward: Vec::new()
+        }
+    }
+
+    /// records that the user left `from` for a new location, a new navigation drops the forward stack
+    pub fn push(&mut self, from: PathBuf) {
+        if self.back.last() != Some(&from) {
+            self.back.push(from);
+        }
+        self.forward.clear();
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+
+    /// the back entries, the most recent one first
+    pub fn back_entries(&self) -> impl Iterator<Item = &PathBuf> {
+        self.back.iter().rev()
+    }
+
+    /// the forward entries, the nearest one first
+    pub fn forward_entries(&self) -> impl Iterator<Item = &PathBuf> {
+        self.forward.iter().rev()
+    }
+
+    pub fn peek_back(&self, steps: usize) -> Option<&PathBuf> {
+        peek(&self.back, steps)
+    }
+
+    pub fn peek_forward(&self, steps: usize) -> Option<&PathBuf> {
+        peek(&self.forward, steps)
+    }
+
+    /// moves `steps` entries back, `current` is pushed to the forward stack
+    pub fn go_back(&mut self, current: PathBuf, steps: usize) -> Option<PathBuf> {
+        step(&mut self.back, &mut self.forward, current, steps)
+    }
+
+    /// moves `steps` entries forward, `current` is pushed to the back stack
+    pub fn go_forward(&mut self, current: PathBuf, steps: usize) -> Option<PathBuf> {
+        step(&mut self.forward, &mut self.back, current, steps)
+    }
+}
+
+fn peek(stack: &[PathBuf], steps: usize) -> Option<&PathBuf> {
+    if steps == 0 {
+        return None;
+    }
+    stack.len().checked_sub(steps).map(|index| &stack[index])
+}
+
+fn step(from: &mut Vec<PathBuf>, to: &mut Vec<PathBuf>, current: PathBuf, steps: usize) -> Option<PathBuf> {
+    if steps == 0 || steps > from.len() {
+        return None;
+    }
+    to.push(current);
+    for _ in 1..steps {
+        let skipped = from.pop().expect("the amount of steps was checked");
+        to.push(skipped);
+    }
+    from.pop()
+}
@@ -1,33 +1,44 @@
 use std::collections::HashMap;
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::thumbnails::{ThumbnailSize, Thumbnailer};
 
 pub struct IconManager {
-    icons: HashMap<String, egui_extras::RetainedImage>,
-    directory_icon: egui_extras::RetainedImage
+    icons: HashMap<(String, i32), egui_extras::RetainedImage>,
+    directory_icon: egui_extras::RetainedImage,
+    thumbnails: Thumbnailer
 }
 
 impl IconManager {
-    pub fn new() -> Self {
+    pub fn new(ctx: egui::Context) -> Self {
         let buffer = include_bytes!("../assets/folder-icon.png");
         let image = egui_extras::RetainedImage::from_image_bytes("assets/folder-icon.png", buffer)
             .expect("unable to read the folder icon image");
         Self {
             icons: HashMap::new(),
-            directory_icon: image
+            directory_icon: image,
+            thumbnails: Thumbnailer::new(ctx)
         }
     }
 
-    pub fn get_icon(&mut self, name: &str) -> Option<&egui_extras::RetainedImage> {
+    pub fn get_icon(&mut self, name: &str, size: i32) -> Option<&egui_extras::RetainedImage> {
         let extension = get_file_extension(name);
-        Some(match self.icons.entry(extension.to_owned()) {
+        Some(match self.icons.entry((extension.to_owned(), size)) {
             std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
             std::collections::hash_map::Entry::Vacant(entry) => {
-                let bytes: Vec<u8> = systemicons::get_icon(&format!(".{extension}"), 16).ok()?;
+                let bytes: Vec<u8> = systemicons::get_icon(&format!(".{extension}"), size).ok()?;
                 let image = egui_extras::RetainedImage::from_image_bytes(extension, &bytes).ok()?;
                 entry.insert(image)
             },
         })
     }
 
+    /// the thumbnail of an image to be shown at `pixels`, None while it is still being made
+    pub fn get_thumbnail(&mut self, path: &Path, modified: SystemTime, pixels: f32) -> Option<&egui_extras::RetainedImage> {
+        self.thumbnails.get(path, modified, ThumbnailSize::for_pixels(pixels))
+    }
+
     pub fn get_directory_icon(&self) -> &egui_extras::RetainedImage {
         &self.directory_icon
     }
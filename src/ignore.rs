@@ -0,0 +1,159 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use regex::Regex;
+
+/// which entries the listings leave out, the same for every tab
+#[derive(Clone)]
+pub struct IgnoreSettings {
+    pub show_hidden: bool,
+    /// the patterns as they were typed, comma separated
+    pub patterns: String,
+    compiled: Vec<Regex>,
+    pub use_gitignore: bool
+}
+
+impl IgnoreSettings {
+    pub fn new() -> Self {
+        Self {
+            show_hidden: false,
+            patterns: String::new(),
+            compiled: Vec::new(),
+            use_gitignore: false
+        }
+    }
+
+    pub fn set_patterns(&mut self, patterns: String) -> Result<(), String> {
+        self.compiled = name_patterns(&patterns)?;
+        self.patterns = patterns;
+        Ok(())
+    }
+
+    /// whether a listing leaves out the entry, `gitignores` are the ones of the folder it is in
+    pub fn hides(&self, path: &Path, is_dir: bool, gitignores: &[Gitignore]) -> bool {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        (!self.show_hidden && is_hidden(&name))
+            || self.compiled.iter().any(|pattern| pattern.is_match(&name))
+            || (self.use_gitignore && is_ignored(gitignores, path, is_dir))
+    }
+}
+
+/// dotfiles are hidden
+pub fn is_hidden(name: &str) -> bool {
+    name.starts_with('.')
+}
+
+/// translates a glob to a regex, `*` and `?` stay inside a path component and `**` crosses them
+pub fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::new();
+    let mut chars = glob.chars().peekable();
+    while let Some(character) = chars.next() {
+        match character {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                }
+                else {
+                    regex.push_str(".*");
+                }
+            },
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                let class: String = chars.by_ref().take_while(|character| *character != ']').collect();
+                let class = class.strip_prefix('!').map(|class| format!("^{class}")).unwrap_or(class);
+                regex.push_str(&format!("[{}]", class.replace('\\', "\\\\")));
+            },
+            character => regex.push_str(&regex::escape(&character.to_string()))
+        }
+    }
+    regex
+}
+
+/// compiles a comma separated list of globs that match names
+pub fn name_patterns(list: &str) -> Result<Vec<Regex>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(|pattern| Regex::new(&format!("^{}$", glob_to_regex(pattern))).map_err(|error| error.to_string()))
+        .collect()
+}
+
+#[derive(Clone)]
+struct IgnoreRule {
+    regex: Regex,
+    negated: bool,
+    dir_only: bool,
+    /// a rule with a slash in it matches the path from the .gitignore folder, otherwise only the name
+    anchored: bool
+}
+
+/// the rules of one .gitignore file, they apply to the paths under the folder it is in
+#[derive(Clone)]
+pub struct Gitignore {
+    base: PathBuf,
+    rules: Arc<Vec<IgnoreRule>>
+}
+
+impl Gitignore {
+    pub fn read(directory: &Path) -> Option<Self> {
+        let content = fs::read_to_string(directory.join(".gitignore")).ok()?;
+        let rules = content.lines().filter_map(parse_ignore_rule).collect();
+        Some(Self { base: directory.to_path_buf(), rules: Arc::new(rules) })
+    }
+
+    /// the .gitignore files that apply to the folder, from the root of its repository down to it.
+    /// a folder outside of a repository has none
+    pub fn for_directory(directory: &Path) -> Vec<Self> {
+        let Some(root) = directory.ancestors().position(|ancestor| ancestor.join(".git").exists()) else {
+            return Vec::new();
+        };
+        let mut ignores: Vec<Self> = directory.ancestors().take(root + 1).filter_map(Self::read).collect();
+        ignores.reverse();
+        ignores
+    }
+}
+
+fn parse_ignore_rule(line: &str) -> Option<IgnoreRule> {
+    let line = line.trim_end();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (negated, pattern) = match line.strip_prefix('!') {
+        Some(pattern) => (true, pattern),
+        None => (false, line.strip_prefix('\\').unwrap_or(line))
+    };
+    let (dir_only, pattern) = match pattern.strip_suffix('/') {
+        Some(pattern) => (true, pattern),
+        None => (false, pattern)
+    };
+    let anchored = pattern.contains('/');
+    let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+    let regex = Regex::new(&format!("^{}$", glob_to_regex(pattern))).ok()?;
+    Some(IgnoreRule { regex, negated, dir_only, anchored })
+}
+
+/// the last rule that matches decides, the deeper .gitignore files come last
+pub fn is_ignored(ignores: &[Gitignore], path: &Path, is_dir: bool) -> bool {
+    let mut ignored = false;
+    for ignore in ignores {
+        let Ok(relative) = path.strip_prefix(&ignore.base) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        for rule in ignore.rules.iter() {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            let subject = if rule.anchored { &relative } else { &name };
+            if rule.regex.is_match(subject) {
+                ignored = !rule.negated;
+            }
+        }
+    }
+    ignored
+}
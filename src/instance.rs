@@ -0,0 +1,184 @@
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+use crate::cli;
+use crate::trash;
+
+/// how long a new explorer waits for the running one to answer
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, PartialEq)]
+pub enum Request {
+    Open(PathBuf),
+    /// files of one folder, they are shown selected together in a single tab
+    Reveal(Vec<PathBuf>),
+    Properties(Vec<PathBuf>),
+    DualPane,
+    Focus
+}
+
+impl Request {
+    /// `open`, `reveal` or `properties` and a path, `dual-pane` or `focus`.
+    /// the path is absolute or a `file://` uri when it isn't UTF-8 or contains a newline
+    fn parse(line: &str) -> Result<Self, String> {
+        let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+        match command {
+            "open" => Ok(Request::Open(parse_path(argument)?)),
+            "reveal" => Ok(Request::Reveal(vec![parse_path(argument)?])),
+            "properties" => Ok(Request::Properties(vec![parse_path(argument)?])),
+            "dual-pane" => Ok(Request::DualPane),
+            "focus" => Ok(Request::Focus),
+            _ => Err(format!("unknown command {command}"))
+        }
+    }
+}
+
+fn parse_path(argument: &str) -> Result<PathBuf, String> {
+    let path = trash::path_from_uri(argument).unwrap_or_else(|| PathBuf::from(argument));
+    if argument.is_empty() {
+        Err(String::from("the path is missing"))
+    }
+    else if !path.is_absolute() {
+        Err(format!("{} is not an absolute path", path.display()))
+    }
+    else if !path.exists() {
+        Err(format!("{} doesn't exist", path.display()))
+    }
+    else {
+        Ok(path)
+    }
+}
+
+/// the path as it is sent in a command
+fn path_argument(path: &Path) -> String {
+    match path.to_str() {
+        Some(text) if !text.contains('\n') && !text.starts_with("file://") => text.to_owned(),
+        _ => format!("file://{}", trash::encode_path(path))
+    }
+}
+
+fn socket_path() -> Option<PathBuf> {
+    let runtime = env::var_os("XDG_RUNTIME_DIR").filter(|runtime| !runtime.is_empty())?;
+    Some(PathBuf::from(runtime).join("file-explorer.socket"))
+}
+
+/// listens for the commands of the explorers started later, the socket is removed when it is dropped.
+/// each line is a command and is answered with `ok` or with `error` and what went wrong, so scripts can use it too
+pub struct Server {
+    path: PathBuf,
+    requests: Receiver<Request>
+}
+
+impl Server {
+    pub fn start(ctx: &egui::Context) -> Result<Self, String> {
+        let path = socket_path().ok_or("The single instance mode needs $XDG_RUNTIME_DIR to be set")?;
+        if UnixStream::connect(&path).is_ok() {
+            return Err(format!("Another file explorer is already listening on {}", path.display()));
+        }
+        // nobody answers on a socket that is left over from an explorer that didn't exit cleanly
+        let _ = fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).map_err(|error| format!("Couldn't listen on {}: {error}", path.display()))?;
+
+        let (sender, requests) = mpsc::channel();
+        let context = ctx.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let sender = sender.clone();
+                let context = context.clone();
+                thread::spawn(move || serve(stream, sender, context));
+            }
+        });
+        Ok(Self { path, requests })
+    }
+
+    pub fn take_requests(&self) -> Vec<Request> {
+        self.requests.try_iter().collect()
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn serve(stream: UnixStream, sender: Sender<Request>, context: egui::Context) {
+    let Ok(reader) = stream.try_clone() else {
+        return;
+    };
+    let mut writer = stream;
+    for line in BufReader::new(reader).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match Request::parse(line) {
+            Ok(request) => {
+                if sender.send(request).is_err() {
+                    break;
+                }
+                context.request_repaint();
+                String::from("ok")
+            },
+            Err(message) => format!("error {message}")
+        };
+        if writeln!(writer, "{reply}").is_err() {
+            break;
+        }
+    }
+}
+
+/// hands the paths to the explorer that is running, returns false when there is none
+pub fn forward(arguments: &cli::Arguments) -> Result<bool, String> {
+    let Some(path) = socket_path() else {
+        return Ok(false);
+    };
+    let Ok(mut stream) = UnixStream::connect(&path) else {
+        return Ok(false);
+    };
+
+    let mut commands = Vec::new();
+    for directory in &arguments.directories {
+        let selected: Vec<&PathBuf> = arguments.selected.iter().filter(|file| file.parent() == Some(directory.as_path())).collect();
+        if selected.is_empty() {
+            commands.push(format!("open {}", path_argument(directory)));
+        }
+        for file in selected {
+            commands.push(format!("reveal {}", path_argument(file)));
+        }
+    }
+    if arguments.dual_pane {
+        commands.push(String::from("dual-pane"));
+    }
+    commands.push(String::from("focus"));
+
+    let error = |error: std::io::Error| format!("couldn't talk to the running file explorer: {error}");
+    stream.set_read_timeout(Some(REPLY_TIMEOUT)).map_err(error)?;
+    for command in &commands {
+        writeln!(stream, "{command}").map_err(error)?;
+    }
+    stream.shutdown(std::net::Shutdown::Write).map_err(error)?;
+
+    let mut errors = Vec::new();
+    for line in BufReader::new(stream).lines().take(commands.len()) {
+        let line = line.map_err(error)?;
+        if let Some(message) = line.strip_prefix("error") {
+            errors.push(message.trim().to_owned());
+        }
+    }
+    if errors.is_empty() {
+        Ok(true)
+    }
+    else {
+        Err(errors.join("\n"))
+    }
+}
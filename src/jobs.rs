@@ -0,0 +1,441 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::file_list::ItemKind;
+use crate::file_operations::{self, OperationObserver};
+use crate::journal::{Fingerprint, Operation, Record};
+use crate::trash;
+
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+const REPAINT_INTERVAL: Duration = Duration::from_millis(100);
+/// how long a job that went well stays in the panel after it finished
+const FINISHED_SHOWN: Duration = Duration::from_secs(3);
+
+pub enum JobKind {
+    /// copies into the destination, with `rename_on_conflict` the copies get a free name instead of failing
+    Copy { sources: Vec<PathBuf>, destination: PathBuf, rename_on_conflict: bool },
+    Move { sources: Vec<PathBuf>, destination: PathBuf },
+    Delete(Vec<PathBuf>),
+    Trash(Vec<PathBuf>),
+    Restore(Vec<trash::TrashItem>),
+    /// deletes the items from the trash for good
+    Purge(Vec<trash::TrashItem>),
+    CreateFile(PathBuf),
+    CreateDirectory(PathBuf),
+    Undo(Operation),
+    Redo(Operation)
+}
+
+impl JobKind {
+    fn description(&self) -> String {
+        match self {
+            JobKind::Copy { sources, destination, .. } => format!("Copying {} to {}", describe_items(sources), destination.display()),
+            JobKind::Move { sources, destination } => format!("Moving {} to {}", describe_items(sources), destination.display()),
+            JobKind::Delete(paths) => format!("Deleting {}", describe_items(paths)),
+            JobKind::Trash(paths) => format!("Moving {} to the trash", describe_items(paths)),
+            JobKind::Restore(items) => {
+                let paths: Vec<PathBuf> = items.iter().map(|item| item.original_path.clone()).collect();
+                format!("Restoring {}", describe_items(&paths))
+            },
+            JobKind::Purge(items) => {
+                let paths: Vec<PathBuf> = items.iter().map(|item| item.original_path.clone()).collect();
+                format!("Deleting {} from the trash", describe_items(&paths))
+            },
+            JobKind::CreateFile(path) => format!("Creating {}", path.display()),
+            JobKind::CreateDirectory(path) => format!("Creating {}", path.display()),
+            JobKind::Undo(operation) => format!("Undoing: {}", operation.description()),
+            JobKind::Redo(operation) => format!("Redoing: {}", operation.description()),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Progress {
+    pub total_files: u64,
+    pub done_files: u64,
+    pub total_bytes: u64,
+    pub done_bytes: u64,
+    pub current_file: Option<PathBuf>,
+    /// the time spent working, without the time the job was paused
+    pub active_time: Duration
+}
+
+impl Progress {
+    pub fn fraction(&self) -> f32 {
+        if self.total_bytes > 0 {
+            self.done_bytes as f32 / self.total_bytes as f32
+        }
+        else if self.total_files > 0 {
+            self.done_files as f32 / self.total_files as f32
+        }
+        else {
+            0.0
+        }
+    }
+
+    pub fn eta(&self) -> Option<Duration> {
+        let fraction = self.fraction() as f64;
+        if fraction <= 0.0 || self.active_time.is_zero() {
+            return None;
+        }
+        let total = self.active_time.as_secs_f64() / fraction;
+        Some(Duration::from_secs_f64((total - self.active_time.as_secs_f64()).max(0.0)))
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum JobState {
+    Running,
+    Paused,
+    Finished,
+    Cancelled,
+    Failed(String)
+}
+
+impl JobState {
+    pub fn is_done(&self) -> bool {
+        matches!(self, JobState::Finished | JobState::Cancelled | JobState::Failed(_))
+    }
+}
+
+struct SharedState {
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+    progress: Mutex<Progress>,
+    state: Mutex<JobState>,
+    records: Mutex<Vec<Record>>
+}
+
+pub struct Job {
+    pub id: u64,
+    pub description: String,
+    shared: Arc<SharedState>,
+    error_reported: bool,
+    /// when the job was first seen finished, it is removed from the panel a moment later
+    finished_at: Option<Instant>
+}
+
+impl Job {
+    pub fn progress(&self) -> Progress {
+        self.shared.progress.lock().expect("a job worker panicked").clone()
+    }
+
+    pub fn state(&self) -> JobState {
+        let state = self.shared.state.lock().expect("a job worker panicked").clone();
+        if state == JobState::Running && self.shared.paused.load(Ordering::Relaxed) {
+            JobState::Paused
+        }
+        else {
+            state
+        }
+    }
+
+    pub fn pause(&self) {
+        self.shared.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.shared.paused.store(false, Ordering::Relaxed);
+    }
+
+    /// the worker stops at the next file or chunk and removes what it already copied
+    pub fn cancel(&self) {
+        self.shared.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// runs the file operations on worker threads so the ui never waits for the filesystem
+pub struct JobQueue {
+    jobs: Vec<Job>,
+    next_id: u64,
+    ctx: egui::Context
+}
+
+impl JobQueue {
+    pub fn new(ctx: egui::Context) -> Self {
+        Self {
+            jobs: Vec::new(),
+            next_id: 0,
+            ctx
+        }
+    }
+
+    pub fn push(&mut self, kind: JobKind) {
+        let shared = Arc::new(SharedState {
+            paused: AtomicBool::new(false),
+            cancelled: AtomicBool::new(false),
+            progress: Mutex::new(Progress::default()),
+            state: Mutex::new(JobState::Running),
+            records: Mutex::new(Vec::new())
+        });
+        let job = Job {
+            id: self.next_id,
+            description: kind.description(),
+            shared: shared.clone(),
+            error_reported: false,
+            finished_at: None
+        };
+        self.next_id += 1;
+
+        let mut worker = Worker {
+            shared,
+            ctx: self.ctx.clone(),
+            last_check: Instant::now(),
+            last_repaint: Instant::now()
+        };
+        thread::spawn(move || {
+            let state = match worker.run(kind) {
+                Ok(()) => JobState::Finished,
+                Err(_) if worker.shared.cancelled.load(Ordering::Relaxed) => JobState::Cancelled,
+                Err(error) => JobState::Failed(error.to_string())
+            };
+            worker.shared.progress.lock().expect("the progress lock is never held while panicking").current_file = None;
+            *worker.shared.state.lock().expect("the state lock is never held while panicking") = state;
+            worker.ctx.request_repaint();
+        });
+        self.jobs.push(job);
+    }
+
+    pub fn jobs(&self) -> &[Job] {
+        &self.jobs
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    /// the errors of jobs that failed since the last call
+    pub fn take_errors(&mut self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for job in &mut self.jobs {
+            if job.error_reported {
+                continue;
+            }
+            if let JobState::Failed(error) = job.state() {
+                job.error_reported = true;
+                errors.push(error);
+            }
+        }
+        errors
+    }
+
+    /// what the jobs did since the last call, for the undo history
+    pub fn take_records(&mut self) -> Vec<Record> {
+        let mut records = Vec::new();
+        for job in &self.jobs {
+            records.append(&mut job.shared.records.lock().expect("the records lock is never held while panicking"));
+        }
+        records
+    }
+
+    pub fn clear_done(&mut self) {
+        self.jobs.retain(|job| !job.state().is_done());
+    }
+
+    /// removes the jobs that went well a moment after they finished,
+    /// the failed and cancelled ones stay until they are cleared
+    pub fn remove_finished(&mut self) {
+        let now = Instant::now();
+        for job in &mut self.jobs {
+            if job.finished_at.is_none() && job.state() == JobState::Finished {
+                job.finished_at = Some(now);
+            }
+        }
+        self.jobs.retain(|job| job.finished_at.map(|at| now - at < FINISHED_SHOWN).unwrap_or(true));
+        let next_removal = self.jobs.iter().filter_map(|job| job.finished_at).map(|at| FINISHED_SHOWN.saturating_sub(now - at)).min();
+        if let Some(next_removal) = next_removal {
+            self.ctx.request_repaint_after(next_removal);
+        }
+    }
+}
+
+struct Worker {
+    shared: Arc<SharedState>,
+    ctx: egui::Context,
+    last_check: Instant,
+    last_repaint: Instant
+}
+
+impl Worker {
+    fn run(&mut self, kind: JobKind) -> io::Result<()> {
+        match kind {
+            JobKind::Copy { sources, destination, rename_on_conflict } => {
+                self.measure(&sources)?;
+                for source in sources {
+                    let copy = if rename_on_conflict {
+                        file_operations::copy_into_with_free_name(&source, &destination, self)?
+                    }
+                    else {
+                        file_operations::copy_into(&source, &destination, self)?
+                    };
+                    let fingerprint = Fingerprint::of_tree(&copy)?;
+                    self.record(Record::Done(Operation::Copy { source, copy, fingerprint }));
+                }
+            },
+            JobKind::Move { sources, destination } => {
+                self.measure(&sources)?;
+                for source in sources {
+                    let to = file_operations::move_into(&source, &destination, self)?;
+                    let fingerprint = Fingerprint::of(&to)?;
+                    self.record(Record::Done(Operation::Move { from: source, to, fingerprint }));
+                }
+            },
+            JobKind::Delete(paths) => {
+                self.measure(&paths)?;
+                for path in paths {
+                    file_operations::remove(&path, self)?;
+                }
+            },
+            JobKind::Trash(paths) => {
+                self.set_total_files(paths.len() as u64);
+                for path in paths {
+                    self.on_file(&path)?;
+                    let item = trash::move_to_trash(&path)?;
+                    self.record(Record::Done(Operation::Trash { item }));
+                }
+            },
+            JobKind::Restore(items) => {
+                self.set_total_files(items.len() as u64);
+                for item in items {
+                    self.on_file(&item.original_path)?;
+                    trash::restore(&item)?;
+                }
+            },
+            JobKind::Purge(items) => {
+                // info files whose item is gone are common, the item counts as deleted already
+                let files: Vec<PathBuf> = items.iter()
+                    .map(trash::TrashItem::file_path)
+                    .filter(|file| fs::symlink_metadata(file).is_ok())
+                    .collect();
+                self.measure(&files)?;
+                for item in items {
+                    let file = item.file_path();
+                    if fs::symlink_metadata(&file).is_ok() {
+                        file_operations::remove(&file, self)?;
+                    }
+                    match fs::remove_file(item.info_path()) {
+                        Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+                        _ => {}
+                    }
+                }
+            },
+            JobKind::CreateFile(path) => {
+                fs::OpenOptions::new().write(true).create_new(true).open(&path)?;
+                let fingerprint = Fingerprint::of(&path)?;
+                self.record(Record::Done(Operation::Create { path, kind: ItemKind::File, fingerprint }));
+            },
+            JobKind::CreateDirectory(path) => {
+                fs::create_dir(&path)?;
+                let fingerprint = Fingerprint::of(&path)?;
+                self.record(Record::Done(Operation::Create { path, kind: ItemKind::Directory, fingerprint }));
+            },
+            JobKind::Undo(operation) => {
+                match operation.undo(self) {
+                    Ok(()) => self.record(Record::Undone(operation)),
+                    Err(error) => {
+                        self.record(Record::UndoFailed(operation));
+                        return Err(error);
+                    }
+                }
+            },
+            JobKind::Redo(operation) => {
+                match operation.redo(self) {
+                    Ok(redone) => self.record(Record::Redone(redone)),
+                    Err(error) => {
+                        self.record(Record::RedoFailed(operation));
+                        return Err(error);
+                    }
+                }
+            },
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, record: Record) {
+        self.shared.records.lock().expect("the records lock is never held while panicking").push(record);
+    }
+
+    fn measure(&mut self, paths: &[PathBuf]) -> io::Result<()> {
+        let mut total_files = 0;
+        let mut total_bytes = 0;
+        for path in paths {
+            self.check_paused_or_cancelled()?;
+            let (files, bytes) = file_operations::measure(path)?;
+            total_files += files;
+            total_bytes += bytes;
+        }
+        self.update_progress(|progress| {
+            progress.total_files = total_files;
+            progress.total_bytes = total_bytes;
+        });
+        Ok(())
+    }
+
+    fn set_total_files(&mut self, total_files: u64) {
+        self.update_progress(|progress| progress.total_files = total_files);
+    }
+
+    /// blocks while the job is paused and fails when it was cancelled
+    fn check_paused_or_cancelled(&mut self) -> io::Result<()> {
+        loop {
+            if self.shared.cancelled.load(Ordering::Relaxed) {
+                return Err(io::Error::new(io::ErrorKind::Interrupted, "the operation was cancelled"));
+            }
+            let now = Instant::now();
+            if !self.shared.paused.load(Ordering::Relaxed) {
+                let elapsed = now - self.last_check;
+                self.last_check = now;
+                self.update_progress(|progress| progress.active_time += elapsed);
+                return Ok(());
+            }
+            self.last_check = now;
+            thread::sleep(PAUSE_POLL_INTERVAL);
+        }
+    }
+
+    fn update_progress(&mut self, update: impl FnOnce(&mut Progress)) {
+        update(&mut self.shared.progress.lock().expect("the progress lock is never held while panicking"));
+        if self.last_repaint.elapsed() >= REPAINT_INTERVAL {
+            self.last_repaint = Instant::now();
+            self.ctx.request_repaint();
+        }
+    }
+}
+
+impl OperationObserver for Worker {
+    fn on_file(&mut self, path: &Path) -> io::Result<()> {
+        self.check_paused_or_cancelled()?;
+        let path = path.to_path_buf();
+        self.update_progress(|progress| {
+            progress.done_files += 1;
+            progress.current_file = Some(path);
+        });
+        Ok(())
+    }
+
+    fn on_bytes(&mut self, bytes: u64) -> io::Result<()> {
+        self.check_paused_or_cancelled()?;
+        self.update_progress(|progress| progress.done_bytes += bytes);
+        Ok(())
+    }
+
+    fn on_renamed(&mut self, files: u64, bytes: u64) -> io::Result<()> {
+        self.update_progress(|progress| {
+            progress.done_files += files;
+            progress.done_bytes += bytes;
+        });
+        Ok(())
+    }
+}
+
+fn describe_items(paths: &[PathBuf]) -> String {
+    match paths {
+        [path] => path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().to_string(),
+        paths => format!("{} items", paths.len())
+    }
+}
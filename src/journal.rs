@@ -0,0 +1,282 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::file_operations::{self, OperationObserver};
+use crate::file_list::ItemKind;
+use crate::trash::{self, TrashItem};
+
+const MAX_ENTRIES: usize = 100;
+
+/// what an item looked like right after an operation, undoing is refused when it changed since
+#[derive(Clone, PartialEq)]
+pub struct Fingerprint {
+    is_dir: bool,
+    len: u64,
+    modified: Option<SystemTime>,
+    /// everything inside of a directory whose content is removed by the undo, by the path relative to it
+    tree: Option<Vec<(PathBuf, Fingerprint)>>
+}
+
+impl Fingerprint {
+    pub fn of(path: &Path) -> io::Result<Self> {
+        let metadata = fs::symlink_metadata(path)?;
+        Ok(Self {
+            is_dir: metadata.is_dir(),
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+            tree: None
+        })
+    }
+
+    /// also takes in the content of a directory, for operations whose undo would remove it
+    pub fn of_tree(path: &Path) -> io::Result<Self> {
+        let mut fingerprint = Self::of(path)?;
+        if fingerprint.is_dir {
+            let mut tree = Vec::new();
+            read_tree(path, Path::new(""), &mut tree)?;
+            fingerprint.tree = Some(tree);
+        }
+        Ok(fingerprint)
+    }
+
+    fn check(&self, path: &Path) -> io::Result<()> {
+        let current = match &self.tree {
+            Some(_) => Self::of_tree(path),
+            None => Self::of(path)
+        };
+        let current = current.map_err(|_| changed_error(path, "was removed since"))?;
+        if self.unchanged(&current) {
+            Ok(())
+        }
+        else {
+            Err(changed_error(path, "was modified since"))
+        }
+    }
+
+    fn unchanged(&self, current: &Self) -> bool {
+        // the modification time of a directory changes with its content, which is compared on its own
+        if self.is_dir {
+            let tree_unchanged = match (&self.tree, &current.tree) {
+                (Some(tree), Some(current_tree)) => tree.len() == current_tree.len()
+                    && tree.iter().zip(current_tree).all(|((path, entry), (current_path, current_entry))| {
+                        path == current_path && entry.unchanged(current_entry)
+                    }),
+                (None, _) => true,
+                (Some(_), None) => false
+            };
+            current.is_dir && tree_unchanged
+        }
+        else {
+            current.is_dir == self.is_dir && current.len == self.len && current.modified == self.modified
+        }
+    }
+}
+
+/// the fingerprints of everything in the directory sorted by path, symlinks aren't followed
+fn read_tree(directory: &Path, relative: &Path, tree: &mut Vec<(PathBuf, Fingerprint)>) -> io::Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(directory)?.collect::<io::Result<_>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let relative = relative.join(entry.file_name());
+        let fingerprint = Fingerprint::of(&path)?;
+        let is_dir = fingerprint.is_dir;
+        tree.push((relative.clone(), fingerprint));
+        if is_dir {
+            read_tree(&path, &relative, tree)?;
+        }
+    }
+    Ok(())
+}
+
+/// a mutating operation with everything that is needed to invert it
+#[derive(Clone)]
+pub enum Operation {
+    Create { path: PathBuf, kind: ItemKind, fingerprint: Fingerprint },
+    Rename { from: PathBuf, to: PathBuf, fingerprint: Fingerprint },
+    Move { from: PathBuf, to: PathBuf, fingerprint: Fingerprint },
+    Copy { source: PathBuf, copy: PathBuf, fingerprint: Fingerprint },
+    Trash { item: TrashItem }
+}
+
+impl Operation {
+    pub fn description(&self) -> String {
+        match self {
+            Operation::Create { path, .. } => format!("Create {}", file_name(path)),
+            Operation::Rename { from, to, .. } => format!("Rename {} to {}", file_name(from), file_name(to)),
+            Operation::Move { from, to, .. } => format!("Move {} to {}", file_name(from), parent_name(to)),
+            Operation::Copy { source, copy, .. } => format!("Copy {} to {}", file_name(source), parent_name(copy)),
+            Operation::Trash { item } => format!("Trash {}", file_name(&item.original_path)),
+        }
+    }
+
+    /// inverts the operation after checking the filesystem still looks the way the operation left it
+    pub fn undo(&self, observer: &mut impl OperationObserver) -> io::Result<()> {
+        match self {
+            Operation::Create { path, kind, fingerprint } => {
+                fingerprint.check(path)?;
+                match kind {
+                    ItemKind::File => fs::remove_file(path),
+                    // only an empty directory is removed, anything put inside of it stays
+                    ItemKind::Directory => fs::remove_dir(path).map_err(|_| changed_error(path, "is not empty"))
+                }
+            },
+            Operation::Rename { from, to, fingerprint } | Operation::Move { from, to, fingerprint } => {
+                fingerprint.check(to)?;
+                file_operations::move_to(to, from, observer)
+            },
+            Operation::Copy { copy, fingerprint, .. } => {
+                fingerprint.check(copy)?;
+                file_operations::remove(copy, observer)
+            },
+            Operation::Trash { item } => {
+                if fs::symlink_metadata(item.file_path()).is_err() {
+                    return Err(changed_error(&item.original_path, "is no longer in the trash"));
+                }
+                trash::restore(item)
+            },
+        }
+    }
+
+    /// runs the operation again, the returned operation describes the new result
+    pub fn redo(&self, observer: &mut impl OperationObserver) -> io::Result<Operation> {
+        match self {
+            Operation::Create { path, kind, .. } => {
+                match kind {
+                    ItemKind::File => fs::OpenOptions::new().write(true).create_new(true).open(path).map(|_| ())?,
+                    ItemKind::Directory => fs::create_dir(path)?
+                }
+                Ok(Operation::Create { path: path.clone(), kind: kind.clone(), fingerprint: Fingerprint::of(path)? })
+            },
+            Operation::Rename { from, to, .. } => {
+                file_operations::move_to(from, to, observer)?;
+                Ok(Operation::Rename { from: from.clone(), to: to.clone(), fingerprint: Fingerprint::of(to)? })
+            },
+            Operation::Move { from, to, .. } => {
+                file_operations::move_to(from, to, observer)?;
+                Ok(Operation::Move { from: from.clone(), to: to.clone(), fingerprint: Fingerprint::of(to)? })
+            },
+            Operation::Copy { source, copy, .. } => {
+                file_operations::copy_to(source, copy, observer)?;
+                Ok(Operation::Copy { source: source.clone(), copy: copy.clone(), fingerprint: Fingerprint::of_tree(copy)? })
+            },
+            Operation::Trash { item } => {
+                let item = trash::move_to_trash(&item.original_path)?;
+                Ok(Operation::Trash { item })
+            },
+        }
+    }
+}
+
+/// what a job did, sent back to the journal once the job is done
+pub enum Record {
+    Done(Operation),
+    Undone(Operation),
+    Redone(Operation),
+    /// the undo was refused or failed, the operation goes back where it was taken from
+    UndoFailed(Operation),
+    RedoFailed(Operation)
+}
+
+pub struct Journal {
+    undo: Vec<Operation>,
+    redo: Vec<Operation>,
+    /// an undo or redo is running, the next one waits for it so they can't finish out of order
+    busy: bool,
+    /// the operations of other jobs that finished meanwhile, they are recorded after it
+    /// so they clear the redo stack the undo or redo fills
+    pending: Vec<Operation>
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            busy: false,
+            pending: Vec::new()
+        }
+    }
+
+    pub fn apply(&mut self, record: Record) {
+        match record {
+            Record::Done(operation) if self.busy => self.pending.push(operation),
+            Record::Done(operation) => self.record_done(operation),
+            Record::Undone(operation) | Record::RedoFailed(operation) => {
+                self.redo.push(operation);
+                self.finish();
+            },
+            Record::Redone(operation) | Record::UndoFailed(operation) => {
+                self.push_undo(operation);
+                self.finish();
+            },
+        }
+    }
+
+    fn record_done(&mut self, operation: Operation) {
+        self.redo.clear();
+        self.push_undo(operation);
+    }
+
+    /// the undo or redo is done, what finished while it ran is recorded now
+    fn finish(&mut self) {
+        self.busy = false;
+        for operation in std::mem::take(&mut self.pending) {
+            self.record_done(operation);
+        }
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.busy
+    }
+
+    /// takes the operation to undo, none while another undo or redo is still running
+    pub fn pop_undo(&mut self) -> Option<Operation> {
+        if self.busy {
+            return None;
+        }
+        let operation = self.undo.pop();
+        self.busy = operation.is_some();
+        operation
+    }
+
+    pub fn pop_redo(&mut self) -> Option<Operation> {
+        if self.busy {
+            return None;
+        }
+        let operation = self.redo.pop();
+        self.busy = operation.is_some();
+        operation
+    }
+
+    /// the operations that can be undone, the most recent one first
+    pub fn undo_entries(&self) -> impl Iterator<Item = &Operation> {
+        self.undo.iter().rev()
+    }
+
+    /// the operations that can be redone, the next one first
+    pub fn redo_entries(&self) -> impl Iterator<Item = &Operation> {
+        self.redo.iter().rev()
+    }
+
+    fn push_undo(&mut self, operation: Operation) {
+        if self.undo.len() == MAX_ENTRIES {
+            self.undo.remove(0);
+        }
+        self.undo.push(operation);
+    }
+}
+
+fn changed_error(path: &Path, what: &str) -> io::Error {
+    io::Error::other(format!("{} {what}, it is left as it is", path.display()))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().to_string()
+}
+
+fn parent_name(path: &Path) -> String {
+    path.parent().map(file_name).unwrap_or_default()
+}
@@ -0,0 +1,110 @@
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::details;
+use crate::ignore;
+
+/// the path that was typed, `~` is home, `$NAME` and `${NAME}` are environment variables
+/// and a relative path is relative to the current directory
+pub fn expand(text: &str, current: &Path) -> PathBuf {
+    let text = expand_variables(text.trim());
+    let path = match text.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => match env::var_os("HOME") {
+            Some(home) => PathBuf::from(home).join(rest.trim_start_matches('/')),
+            None => PathBuf::from(&text)
+        },
+        _ => PathBuf::from(&text)
+    };
+    normalize(&current.join(path))
+}
+
+/// variables that aren't set are left as they were typed
+fn expand_variables(text: &str) -> String {
+    let mut expanded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let (name, length) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0)
+            },
+            None => {
+                let end = after.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+        match env::var(name).ok().filter(|_| !name.is_empty()) {
+            Some(value) => expanded.push_str(&value),
+            None => expanded.push_str(&rest[start..start + 1 + length])
+        }
+        rest = &after[length..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// removes the `.` and `..` components without following symlinks, the way a shell's `cd` does
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => {
+                normalized.pop();
+            },
+            component => normalized.push(component)
+        }
+    }
+    normalized
+}
+
+/// the typed text split after its last slash, into the folder and the start of the name in it
+pub fn split_typed(text: &str) -> (&str, &str) {
+    match text.rfind('/') {
+        Some(slash) => text.split_at(slash + 1),
+        None => ("", text)
+    }
+}
+
+/// the names of the folders in the typed folder, sorted
+pub fn folder_names(directory: &str, current: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(expand(directory, current)) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort_by(|a, b| details::natural_cmp(a, b));
+    names
+}
+
+/// the typed text completed with each of the folder names that starts with the last part of it.
+/// hidden folders are only offered once a dot was typed
+pub fn completions(text: &str, names: &[String]) -> Vec<String> {
+    let (directory, prefix) = split_typed(text);
+    names.iter()
+        .filter(|name| name.starts_with(prefix) && (prefix.starts_with('.') || !ignore::is_hidden(name)))
+        .map(|name| format!("{directory}{name}/"))
+        .collect()
+}
+
+/// what tab completes the text to, the part all the completions have in common
+pub fn complete(completions: &[String]) -> Option<String> {
+    let first = completions.first()?;
+    let mut length = first.len();
+    for completion in &completions[1..] {
+        length = first.char_indices()
+            .zip(completion.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map(|((index, character), _)| index + character.len_utf8())
+            .unwrap_or(0)
+            .min(length);
+    }
+    Some(first[..length].to_owned())
+}
@@ -3,78 +3,467 @@ mod gui_extension;
 mod file_list;
 mod watcher;
 mod icon_manager;
+mod thumbnails;
+mod history;
+mod tab;
+mod pane;
+mod file_operations;
+mod clipboard;
+mod jobs;
+mod trash;
+mod trash_view;
+mod journal;
+mod metadata;
+mod details;
+mod selection;
+mod bulk_rename;
+mod highlight;
+mod preview;
+mod search;
+mod search_view;
+mod quick_filter;
+mod ignore;
+mod tree;
+mod places;
+mod location;
+mod settings;
+mod cli;
+mod instance;
+mod file_manager;
 use std::collections::VecDeque;
 use std::env;
 use std::fs;
 use std::path::PathBuf;
+use std::process;
 use eframe::egui;
-use file_list::FileListItem;
-use watcher::Watcher;
+use gui_extension::UiHelpersExt;
 
 fn main() {
-    eframe::run_native("file explorer", Default::default(), Box::new(|cc| Box::new(FileExplorer::new(cc)))).unwrap();
+    let arguments = match cli::parse(env::args_os().skip(1)) {
+        Ok(cli::Command::Run(arguments)) => arguments,
+        Ok(cli::Command::Help) => {
+            print!("{}", cli::USAGE);
+            return;
+        },
+        Ok(cli::Command::Version) => {
+            println!("file-explorer {}", env!("CARGO_PKG_VERSION"));
+            return;
+        },
+        Err(message) => {
+            eprintln!("file-explorer: {message}");
+            eprintln!("Try 'file-explorer --help' for more information.");
+            process::exit(2);
+        }
+    };
+    let (settings, settings_error) = settings::Settings::load();
+    if settings.single_instance && !arguments.new_window {
+        match instance::forward(&arguments) {
+            Ok(true) => return,
+            Ok(false) => {},
+            Err(message) => {
+                eprintln!("file-explorer: {message}");
+                process::exit(1);
+            }
+        }
+    }
+    let options = eframe::NativeOptions {
+        initial_window_size: settings.window_size.map(egui::Vec2::from),
+        initial_window_pos: settings.window_position.map(egui::Pos2::from),
+        maximized: settings.window_maximized,
+        follow_system_theme: settings.theme == settings::Theme::System,
+        ..Default::default()
+    };
+    eframe::run_native("file explorer", options, Box::new(|cc| Box::new(FileExplorer::new(cc, settings, settings_error, arguments)))).unwrap();
+}
+
+const WINDOW_ROUNDING: f32 = 5.0;
+
+/// the visuals of the theme with the rounding and margins of the explorer
+fn set_style(ctx: &egui::Context, dark: bool) {
+    let mut style = (*ctx.style()).clone();
+    style.visuals = if dark { egui::Visuals::dark() } else { egui::Visuals::light() };
+    style.visuals.window_rounding = egui::Rounding::same(WINDOW_ROUNDING);
+    style.visuals.menu_rounding = egui::Rounding::none();
+    style.spacing.menu_margin = egui::Margin::same(2.0);
+    ctx.set_style(style);
 }
+
 struct FileExplorer {
-    child_directories: Vec<file_list::FileListItem>,
-    directory: PathBuf,
+    panes: Vec<pane::Pane>,
+    active_pane: usize,
+    dual_pane: bool,
     error_dialogs: VecDeque<String>,
-    delete_dialog: Option<PathBuf>,
+    delete_dialog: Option<Vec<PathBuf>>,
+    /// the trash items to delete permanently once it is confirmed
+    purge_dialog: Option<Vec<trash::TrashItem>>,
+    bulk_rename: Option<bulk_rename::BulkRename>,
+    trash_view: Option<trash_view::TrashView>,
+    search_view: Option<search_view::SearchView>,
+    clipboard: Option<clipboard::Clipboard>,
+    jobs: jobs::JobQueue,
+    journal: journal::Journal,
+    show_journal: bool,
+    preview: preview::PreviewPanel,
+    show_preview: bool,
+    places: places::Places,
+    tree: tree::DirectoryTree,
+    show_sidebar: bool,
+    ignore: ignore::IgnoreSettings,
+    /// the ignore patterns while they are edited, they are applied when the field loses focus
+    ignore_patterns: String,
+    settings: settings::Settings,
+    show_settings: bool,
+    /// listens for the paths of the explorers started later in the single instance mode
+    instance: Option<instance::Server>,
+    /// the D-Bus interface other programs call to show files in the file manager
+    file_manager: Option<file_manager::FileManagerService>,
     file_icons_manager: icon_manager::IconManager,
-    watcher: Box<dyn Watcher>,
-    file_list: file_list::FileListWidget
+    context: egui::Context
+}
+
+enum Transfer {
+    Copy,
+    Move
 }
 
 impl FileExplorer {
-    fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        let current_dir = env::current_dir().expect("Couldnt get the working directory!");
-
-        let mut style = (*cc.egui_ctx.style()).clone();
-        style.visuals.window_rounding = egui::Rounding::same(5.0);
-        style.visuals.menu_rounding = egui::Rounding::none();
-        style.spacing.menu_margin = egui::Margin::same(2.0);
-        cc.egui_ctx.set_style(style);
-
-        let context = cc.egui_ctx.clone();
-        let watcher = Box::new(watcher::FileSystemWatcher::new(current_dir.clone(), move || {
-            context.request_repaint();
-        }));
-
-        let mut explorer =  Self { 
-            directory: current_dir,
-            child_directories: Vec::new(),
-            error_dialogs: VecDeque::new(),
-            delete_dialog: None,
-            file_icons_manager: icon_manager::IconManager::new(),
-            watcher: watcher,
-            file_list: file_list::FileListWidget::new()
+    fn new(cc: &eframe::CreationContext<'_>, settings: settings::Settings, settings_error: Option<String>, arguments: cli::Arguments) -> Self {
+        set_style(&cc.egui_ctx, settings.theme.is_dark(cc.integration_info.system_theme));
+
+        let mut error_dialogs = VecDeque::new();
+        error_dialogs.extend(settings_error);
+        let mut ignore = ignore::IgnoreSettings::new();
+        ignore.show_hidden = settings.show_hidden;
+        ignore.use_gitignore = settings.use_gitignore;
+        if let Err(error) = ignore.set_patterns(settings.ignore_patterns.clone()) {
+            error_dialogs.push_front(error);
+        }
+
+        let instance = if settings.single_instance && !arguments.new_window {
+            instance::Server::start(&cc.egui_ctx).map_err(|error| error_dialogs.push_front(error)).ok()
+        }
+        else {
+            None
         };
 
-        explorer.refresh_childs();
+        let file_manager = if settings.file_manager_service && !arguments.new_window {
+            file_manager::FileManagerService::start(&cc.egui_ctx).map_err(|error| error_dialogs.push_front(error)).ok()
+        }
+        else {
+            None
+        };
 
+        let mut explorer = Self { 
+            panes: Vec::new(),
+            active_pane: 0,
+            dual_pane: false,
+            error_dialogs,
+            delete_dialog: None,
+            purge_dialog: None,
+            bulk_rename: None,
+            trash_view: None,
+            search_view: None,
+            clipboard: None,
+            jobs: jobs::JobQueue::new(cc.egui_ctx.clone()),
+            journal: journal::Journal::new(),
+            show_journal: false,
+            preview: preview::PreviewPanel::new(cc.egui_ctx.clone()),
+            show_preview: settings.show_preview,
+            places: places::Places::new(),
+            tree: tree::DirectoryTree::new(ignore.clone()),
+            show_sidebar: settings.show_sidebar,
+            ignore_patterns: ignore.patterns.clone(),
+            ignore,
+            settings,
+            show_settings: false,
+            instance,
+            file_manager,
+            file_icons_manager: icon_manager::IconManager::new(cc.egui_ctx.clone()),
+            context: cc.egui_ctx.clone()
+        };
+        explorer.open_startup_tabs(arguments);
         explorer
     }
 
-    fn refresh_childs(&mut self) {
-        self.child_directories.clear();
-        if let Ok(entries) = fs::read_dir(&self.directory) {
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    self.child_directories.push(FileListItem::new(entry.path()));
+    /// opens the paths from the command line, without any the tabs of the last session are opened again
+    fn open_startup_tabs(&mut self, arguments: cli::Arguments) {
+        if arguments.directories.is_empty() && !arguments.new_window && self.settings.restore_session {
+            self.restore_session();
+        }
+        let mut tabs = Vec::new();
+        for directory in arguments.directories {
+            match self.new_tab(directory) {
+                Ok(tab) => tabs.push(tab),
+                Err(message) => self.report_error(message)
+            }
+        }
+        if arguments.dual_pane && tabs.len() > 1 {
+            let right = tabs.split_off(tabs.len() - 1);
+            self.panes.push(pane::Pane { tabs, active_tab: 0 });
+            self.panes.push(pane::Pane { tabs: right, active_tab: 0 });
+        }
+        else if !tabs.is_empty() {
+            self.panes.push(pane::Pane { tabs, active_tab: 0 });
+        }
+        if self.panes.is_empty() {
+            let tab = self.fallback_tab();
+            self.panes.push(pane::Pane::new(tab));
+        }
+
+        let tabs = self.panes.iter_mut().flat_map(|pane| pane.tabs.iter_mut());
+        for tab in tabs {
+            let files: Vec<PathBuf> = arguments.selected.iter()
+                .filter(|file| file.parent() == Some(tab.directory.as_path()))
+                .cloned()
+                .collect();
+            if let Err(message) = tab.reveal_all(&files) {
+                self.error_dialogs.push_front(message);
+            }
+        }
+        if arguments.dual_pane {
+            self.set_dual_pane(true);
+        }
+    }
+
+    /// opens the tabs of the last session, the folders that are gone since are left out
+    fn restore_session(&mut self) {
+        let session = self.settings.session.clone();
+        for pane_session in session.panes.iter().take(2) {
+            let tabs: Vec<tab::Tab> = pane_session.tabs.iter()
+                .filter_map(|path| self.new_tab(path.clone()).ok())
+                .collect();
+            if !tabs.is_empty() {
+                let active_tab = pane_session.active_tab.min(tabs.len() - 1);
+                self.panes.push(pane::Pane { tabs, active_tab });
+            }
+        }
+        self.dual_pane = session.dual_pane && self.panes.len() > 1;
+        if self.dual_pane {
+            self.active_pane = session.active_pane.min(1);
+        }
+    }
+
+    /// the working directory, or home or the root when it can't be opened
+    fn fallback_tab(&mut self) -> tab::Tab {
+        let mut directories = Vec::new();
+        match env::current_dir() {
+            Ok(directory) => directories.push(directory),
+            Err(error) => self.report_error(format!("Couldn't get the working directory: {error}"))
+        }
+        directories.extend(env::var_os("HOME").map(PathBuf::from));
+        for directory in directories {
+            match self.new_tab(directory) {
+                Ok(tab) => return tab,
+                Err(message) => self.report_error(message)
+            }
+        }
+        self.new_tab(PathBuf::from("/")).expect("Couldnt open the root directory!")
+    }
+
+    /// takes the current state into the settings and writes them
+    fn save_settings(&mut self) -> Result<(), String> {
+        self.settings.take_view(self.panes[self.active_pane].tab());
+        self.settings.show_sidebar = self.show_sidebar;
+        self.settings.show_preview = self.show_preview;
+        self.settings.session = settings::Session {
+            dual_pane: self.dual_pane,
+            active_pane: self.active_pane,
+            panes: self.panes.iter()
+                .map(|pane| settings::PaneSession {
+                    tabs: pane.tabs.iter().map(|tab| tab.directory.clone()).collect(),
+                    active_tab: pane.active_tab
+                })
+                .collect()
+        };
+        self.settings.save()
+    }
+
+    /// applies what was changed in the settings window to everything that is open
+    fn apply_settings(&mut self, previous: &settings::Settings) {
+        let view_changed = self.settings.view_mode != previous.view_mode
+            || self.settings.icon_size != previous.icon_size
+            || self.settings.sort_column != previous.sort_column
+            || self.settings.sort_order != previous.sort_order
+            || self.settings.folders_first != previous.folders_first;
+        if view_changed {
+            for pane in &mut self.panes {
+                for tab in &mut pane.tabs {
+                    self.settings.apply_view(tab);
+                }
+            }
+        }
+        if self.settings.show_hidden != previous.show_hidden || self.settings.use_gitignore != previous.use_gitignore {
+            let mut ignore = self.ignore.clone();
+            ignore.show_hidden = self.settings.show_hidden;
+            ignore.use_gitignore = self.settings.use_gitignore;
+            self.set_ignore(ignore);
+        }
+        if self.settings.single_instance != previous.single_instance {
+            self.instance = None;
+            if self.settings.single_instance {
+                match instance::Server::start(&self.context) {
+                    Ok(server) => self.instance = Some(server),
+                    Err(message) => self.report_error(message)
                 }
             }
         }
+        if self.settings.file_manager_service != previous.file_manager_service {
+            self.file_manager = None;
+            if self.settings.file_manager_service {
+                match file_manager::FileManagerService::start(&self.context) {
+                    Ok(service) => self.file_manager = Some(service),
+                    Err(message) => self.report_error(message)
+                }
+            }
+        }
+        if let Err(message) = self.save_settings() {
+            self.report_error(message);
+        }
     }
 
-    fn change_dir(&mut self, path: PathBuf) -> Result<(), String> {
-        self.watcher.watch(path.clone())?;
-        self.directory = path;
-        self.refresh_childs();
-        Ok(())
+    /// opens the folder of the paths in a new tab with all of them selected, they share one parent
+    fn reveal_in_new_tab(&mut self, paths: &[PathBuf]) {
+        let Some(parent) = paths.first().and_then(|path| path.parent()) else {
+            return;
+        };
+        self.open_tab(parent.to_path_buf());
+        if let Err(message) = self.tab_mut().reveal_all(paths) {
+            self.report_error(message);
+        }
+    }
+
+    /// handles what an explorer that was started later or another program over D-Bus handed over
+    fn handle_instance_request(&mut self, request: instance::Request, frame: &mut eframe::Frame) {
+        match request {
+            instance::Request::Open(path) if path.is_dir() => self.open_tab(path),
+            instance::Request::Open(path) => self.reveal_in_new_tab(&[path]),
+            instance::Request::Reveal(paths) => self.reveal_in_new_tab(&paths),
+            instance::Request::Properties(paths) => {
+                self.reveal_in_new_tab(&paths);
+                self.show_preview = true;
+            },
+            instance::Request::DualPane => self.set_dual_pane(true),
+            instance::Request::Focus => frame.focus()
+        }
+    }
+
+    fn pane(&self) -> &pane::Pane {
+        &self.panes[self.active_pane]
+    }
+
+    fn pane_mut(&mut self) -> &mut pane::Pane {
+        &mut self.panes[self.active_pane]
+    }
+
+    fn tab(&self) -> &tab::Tab {
+        self.pane().tab()
+    }
+
+    fn tab_mut(&mut self) -> &mut tab::Tab {
+        self.pane_mut().tab_mut()
+    }
+
+    /// a tab shown the way the settings say new tabs are
+    fn new_tab(&self, path: PathBuf) -> Result<tab::Tab, String> {
+        let mut tab = tab::Tab::new(path, self.ignore.clone(), &self.context)?;
+        self.settings.apply_view(&mut tab);
+        Ok(tab)
+    }
+
+    fn open_tab(&mut self, path: PathBuf) {
+        match self.new_tab(path) {
+            Ok(tab) => self.pane_mut().open_tab(tab),
+            Err(message) => self.report_error(message)
+        }
+    }
+
+    fn set_dual_pane(&mut self, dual_pane: bool) {
+        if dual_pane && self.panes.len() < 2 {
+            match self.new_tab(self.tab().directory.clone()) {
+                Ok(tab) => self.panes.push(pane::Pane::new(tab)),
+                Err(message) => {
+                    self.report_error(message);
+                    return;
+                }
+            }
+        }
+        self.dual_pane = dual_pane;
+        if !dual_pane {
+            self.active_pane = 0;
+        }
+    }
+
+    /// lists every open tab again with the new settings
+    fn set_ignore(&mut self, ignore: ignore::IgnoreSettings) {
+        self.settings.show_hidden = ignore.show_hidden;
+        self.settings.ignore_patterns = ignore.patterns.clone();
+        self.settings.use_gitignore = ignore.use_gitignore;
+        self.ignore = ignore;
+        self.tree.set_ignore(self.ignore.clone());
+        for pane in &mut self.panes {
+            for tab in &mut pane.tabs {
+                tab.set_ignore(self.ignore.clone());
+            }
+        }
+    }
+
+    fn toggle_hidden(&mut self) {
+        let mut ignore = self.ignore.clone();
+        ignore.show_hidden = !ignore.show_hidden;
+        self.set_ignore(ignore);
+    }
+
+    /// copies or moves the selection of the active pane into the directory of the other pane
+    fn transfer_to_other_pane(&mut self, transfer: Transfer) {
+        if !self.dual_pane {
+            return;
+        }
+        let destination = self.panes[1 - self.active_pane].tab().directory.clone();
+        let sources = self.pane().selected_paths();
+        if sources.is_empty() {
+            return;
+        }
+        let job = match transfer {
+            Transfer::Copy => jobs::JobKind::Copy { sources, destination, rename_on_conflict: false },
+            Transfer::Move => jobs::JobKind::Move { sources, destination }
+        };
+        self.jobs.push(job);
+    }
+
+    fn copy_selection_to_clipboard(&mut self, mode: clipboard::ClipboardMode) {
+        let paths = self.pane().selected_paths();
+        if !paths.is_empty() {
+            self.clipboard = Some(clipboard::Clipboard::new(paths, mode));
+        }
+    }
+
+    /// pastes into the current directory, the new items are picked up by the watcher
+    fn paste(&mut self) {
+        let Some(clipboard) = self.clipboard.take() else {
+            return;
+        };
+        let destination = self.tab().directory.clone();
+        match clipboard.mode {
+            clipboard::ClipboardMode::Copy => {
+                let sources = clipboard.paths.clone();
+                self.jobs.push(jobs::JobKind::Copy { sources, destination, rename_on_conflict: true });
+                self.clipboard = Some(clipboard);
+            },
+            // the cut items are gone from their original place so they cant be pasted again
+            clipboard::ClipboardMode::Cut => {
+                let sources: Vec<PathBuf> = clipboard.paths.into_iter()
+                    .filter(|source| source.parent() != Some(destination.as_path()))
+                    .collect();
+                if !sources.is_empty() {
+                    self.jobs.push(jobs::JobKind::Move { sources, destination });
+                }
+            }
+        }
     }
 
     fn try_open(&mut self, path: PathBuf) -> Result<(), String> {
         if path.is_dir() {
-            self.change_dir(path)?;
+            self.tab_mut().change_dir(path)?;
         }
         else {
             if let Err(err) = open::that(path) {
@@ -85,50 +474,110 @@ impl FileExplorer {
         Ok(())
     }
 
-    fn try_delete_item(&mut self, path: PathBuf) -> Result<(), std::io::Error> {
-        if path.is_file() {
-            if let Ok(metadata) = fs::metadata(&path) {
-                if metadata.len() > 0 {
-                    self.delete_dialog = Some(path);
+    /// empty files and folders are deleted right away, the rest is confirmed first
+    fn try_delete_items(&mut self, paths: Vec<PathBuf>) -> Result<(), std::io::Error> {
+        let mut confirm = Vec::new();
+        let mut delete = Vec::new();
+        for path in paths {
+            if path.is_file() {
+                if let Ok(metadata) = fs::metadata(&path) {
+                    if metadata.len() > 0 {
+                        confirm.push(path);
+                    }
+                    else {
+                        delete.push(path);
+                    }
+                }
+            }
+            else if path.is_dir() {
+                if fs::read_dir(&path)?.next().is_some() {
+                    confirm.push(path);
                 }
                 else {
-                    fs::remove_file(&path)?;
+                    delete.push(path);
                 }
             }
         }
-        else if path.is_dir() {
-            if fs::read_dir(&path)?.next().is_some() {
-                self.delete_dialog = Some(path);
-            }
-            else {
-                fs::remove_dir(&path)?;
-            }
+        if !delete.is_empty() {
+            self.jobs.push(jobs::JobKind::Delete(delete));
+        }
+        if !confirm.is_empty() {
+            self.delete_dialog = Some(confirm);
         }
         Ok(())
     }
 
     fn update_delete_dialog(&mut self, ctx: &egui::Context) {
-        if let Some(path) = &self.delete_dialog {
-            let name = path.file_name().unwrap_or_else(|| std::ffi::OsStr::new("unknown")).to_string_lossy().to_string();
-            let item_type = if path.is_file() {"file"} else if path.is_dir() {"folder"} else {"item"};
+        if let Some(paths) = &self.delete_dialog {
+            let (name, item_type) = match paths.as_slice() {
+                [path] => {
+                    let name = path.file_name().unwrap_or_else(|| std::ffi::OsStr::new("unknown")).to_string_lossy().to_string();
+                    let item_type = if path.is_file() {"file"} else if path.is_dir() {"folder"} else {"item"};
+                    (name, item_type)
+                },
+                paths => (format!("{} items", paths.len()), "selection")
+            };
             if let Some(res) = widgets::delete_dialog(ctx, &name, item_type) {
                 if res {
-                    if path.is_file() {
-                        if let Err(error) = fs::remove_file(path) {
-                            self.report_error(error.to_string());
-                        }
-                    }
-                    else if path.is_dir() {
-                        if let Err(error) = fs::remove_dir_all(path) {
-                            self.report_error(error.to_string());
-                        }
-                    }
+                    self.jobs.push(jobs::JobKind::Delete(paths.clone()));
                 }
                 self.delete_dialog = None;
             }
         }
     }
 
+    fn update_purge_dialog(&mut self, ctx: &egui::Context) {
+        if let Some(items) = &self.purge_dialog {
+            let (name, item_type) = match items.as_slice() {
+                [item] => {
+                    let name = item.original_path.file_name().unwrap_or(item.original_path.as_os_str()).to_string_lossy().to_string();
+                    (name, if item.file_path().is_dir() {"folder"} else {"file"})
+                },
+                items => (format!("{} items", items.len()), "selection")
+            };
+            if let Some(res) = widgets::delete_dialog(ctx, &name, item_type) {
+                if res {
+                    self.jobs.push(jobs::JobKind::Purge(items.clone()));
+                }
+                // the view left the items out already, they are listed again when nothing was deleted
+                else if let Some(trash_view) = &mut self.trash_view {
+                    trash_view.refresh();
+                }
+                self.purge_dialog = None;
+            }
+        }
+    }
+
+    fn update_bulk_rename_dialog(&mut self, ctx: &egui::Context) {
+        if let Some(bulk_rename) = &mut self.bulk_rename {
+            if let Some(renames) = bulk_rename.show(ctx) {
+                self.bulk_rename = None;
+                for (path, name) in renames {
+                    self.rename_item(path, name);
+                }
+            }
+        }
+    }
+
+    fn rename_item(&mut self, path: PathBuf, name: String) {
+        let mut to = path.clone();
+        to.set_file_name(name);
+        if to == path {
+            return;
+        }
+        if to.exists() {
+            self.report_error(format!("{} already exists", to.display()));
+            return;
+        }
+        let result = fs::rename(&path, &to).and_then(|_| journal::Fingerprint::of(&to));
+        match result {
+            Ok(fingerprint) => {
+                self.journal.apply(journal::Record::Done(journal::Operation::Rename { from: path, to, fingerprint }));
+            },
+            Err(error) => self.report_error(error.to_string())
+        }
+    }
+
     fn handle_action(&mut self, action: file_list::FileListAction) {
         match action {
             file_list::FileListAction::Open(path) => {
@@ -137,85 +586,731 @@ impl FileExplorer {
                 }
             },
             file_list::FileListAction::Create(item) => {
+                let path = self.tab().directory.join(item.name);
                 match item.kind {
-                    file_list::ItemKind::File => {
-                        if let Err(error) = fs::write(self.directory.join(item.name), "") {
-                            self.report_error(error.to_string());
-                        }
-                    },
-                    file_list::ItemKind::Directory => {
-                        if let Err(error) = fs::create_dir(self.directory.join(item.name)) {
-                            self.report_error(error.to_string());
-                        }
-                    },
+                    file_list::ItemKind::File => self.jobs.push(jobs::JobKind::CreateFile(path)),
+                    file_list::ItemKind::Directory => self.jobs.push(jobs::JobKind::CreateDirectory(path)),
                 }
             }, 
-            file_list::FileListAction::Delete(path) => {
-                if let Err(error) = self.try_delete_item(path) {
-                    self.report_error(error.to_string());
+            file_list::FileListAction::Trash => {
+                let selected = self.pane().selected_paths();
+                if !selected.is_empty() {
+                    self.jobs.push(jobs::JobKind::Trash(selected));
                 }
             },
-            file_list::FileListAction::Rename(path, name) => {
-                let mut to = path.clone();
-                to.set_file_name(name);
-                if let Err(error) = fs::rename(path, to) {
+            file_list::FileListAction::Delete => {
+                let selected = self.pane().selected_paths();
+                if let Err(error) = self.try_delete_items(selected) {
                     self.report_error(error.to_string());
                 }
             },
-            file_list::FileListAction::Select(index) => {
-                self.child_directories[index].selected = true;
+            file_list::FileListAction::Rename(path, name) => {
+                self.rename_item(path, name);
+            },
+            file_list::FileListAction::RenameSelection => {
+                self.bulk_rename = Some(bulk_rename::BulkRename::new(self.pane().selected_paths()));
+            },
+            file_list::FileListAction::Copy => {
+                self.copy_selection_to_clipboard(clipboard::ClipboardMode::Copy);
             },
-            file_list::FileListAction::Deselect(index) => {
-                self.child_directories[index].selected = false;
+            file_list::FileListAction::Cut => {
+                self.copy_selection_to_clipboard(clipboard::ClipboardMode::Cut);
+            },
+            file_list::FileListAction::Paste => {
+                self.paste();
+            },
+            file_list::FileListAction::Bookmark(path) => {
+                self.places.add_bookmark(path);
+            },
+            file_list::FileListAction::OpenInNewTab(path) => {
+                self.open_tab(path);
+            },
+            file_list::FileListAction::Select(index, modifiers) => {
+                let tab = self.tab_mut();
+                tab.selection.click(&mut tab.child_directories, index, modifiers);
+            },
+            file_list::FileListAction::ContextSelect(index) => {
+                let tab = self.tab_mut();
+                tab.selection.context_click(&mut tab.child_directories, index);
+            },
+            file_list::FileListAction::SelectMany(indices, additive) => {
+                let tab = self.tab_mut();
+                tab.selection.select_many(&mut tab.child_directories, &indices, additive);
+            },
+            file_list::FileListAction::SelectAll => {
+                let tab = self.tab_mut();
+                tab.selection.select_all(&mut tab.child_directories);
+            },
+            file_list::FileListAction::InvertSelection => {
+                let tab = self.tab_mut();
+                tab.selection.invert(&mut tab.child_directories);
+            },
+            file_list::FileListAction::DeselectAll => {
+                let tab = self.tab_mut();
+                tab.selection.clear(&mut tab.child_directories);
+            },
+            file_list::FileListAction::Sort => {
+                self.tab_mut().sort_childs();
             },
         }
     }
 
-    fn report_error(&mut self, message: String) {
-        self.error_dialogs.push_front(message);
+    fn handle_selection_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (select_all, invert) = ctx.input_mut(|input| {
+            let select_all = input.consume_key(egui::Modifiers::COMMAND, egui::Key::A);
+            let invert = input.consume_key(egui::Modifiers::COMMAND, egui::Key::I);
+            (select_all, invert)
+        });
+        if select_all {
+            self.handle_action(file_list::FileListAction::SelectAll);
+        }
+        if invert {
+            self.handle_action(file_list::FileListAction::InvertSelection);
+        }
+
+        // up and down move by a whole row, left and right only mean something when a row has more than one item
+        let items_per_row = self.tab().file_list.items_per_row() as isize;
+        let items_per_page = self.tab().file_list.items_per_page() as isize;
+        let item_count = self.tab().child_directories.len() as isize;
+        let mut keys = vec![
+            (egui::Key::ArrowUp, -items_per_row),
+            (egui::Key::ArrowDown, items_per_row),
+            (egui::Key::PageUp, -items_per_page),
+            (egui::Key::PageDown, items_per_page),
+            (egui::Key::Home, -item_count),
+            (egui::Key::End, item_count)
+        ];
+        if items_per_row > 1 {
+            keys.extend([(egui::Key::ArrowLeft, -1), (egui::Key::ArrowRight, 1)]);
+        }
+        // shift extends the selection and ctrl moves only the focus, ctrl+space selects the focused item then
+        let (step, toggle) = ctx.input_mut(|input| {
+            let step = keys.iter().find_map(|(key, delta)| {
+                [egui::Modifiers::NONE, egui::Modifiers::SHIFT, egui::Modifiers::COMMAND]
+                    .into_iter()
+                    .find(|modifiers| input.consume_key(*modifiers, *key))
+                    .map(|modifiers| (*delta, modifiers))
+            });
+            let toggle = input.consume_key(egui::Modifiers::COMMAND, egui::Key::Space);
+            (step, toggle)
+        });
+        let tab = self.tab_mut();
+        if let Some((delta, modifiers)) = step {
+            let index = if modifiers.command {
+                tab.selection.move_focus(&tab.child_directories, delta)
+            }
+            else {
+                tab.selection.step(&mut tab.child_directories, delta, modifiers.shift)
+            };
+            if let Some(index) = index {
+                tab.file_list.scroll_to(index);
+            }
+        }
+        if toggle {
+            tab.selection.toggle_focus(&mut tab.child_directories);
+        }
     }
-}
 
-impl eframe::App for FileExplorer {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        if let Some(change) = self.watcher.look_for_changes() {
-            match change {
-                watcher::Change::Unknown => self.refresh_childs(),
-                watcher::Change::Create(_kind, path) => self.child_directories.push(FileListItem::new(path)),
-                watcher::Change::Remove(path) => self.child_directories.retain(|p| *p.path != path),
-                watcher::Change::Rename(from, to) => {
-                    let item = self.child_directories.iter_mut().find(|p| *p.path == from);
-                    if let Some(item) = item {
-                        *item = FileListItem::new(to);
+    /// enter opens the focused item and F2 renames the selection
+    fn handle_item_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (open, rename) = ctx.input_mut(|input| {
+            let open = input.consume_key(egui::Modifiers::NONE, egui::Key::Enter);
+            let rename = input.consume_key(egui::Modifiers::NONE, egui::Key::F2);
+            (open, rename)
+        });
+        let tab = self.tab();
+        if open {
+            let focused = tab.selection.focus
+                .and_then(|focus| tab.child_directories.get(focus))
+                .filter(|item| item.visible)
+                .or_else(|| tab.child_directories.iter().find(|item| item.selected));
+            if let Some(item) = focused {
+                self.handle_action(file_list::FileListAction::Open(item.path.clone()));
+            }
+        }
+        if rename {
+            match self.pane().selected_paths().len() {
+                0 => {},
+                1 => self.tab_mut().file_list.rename(),
+                _ => self.handle_action(file_list::FileListAction::RenameSelection)
+            }
+        }
+    }
+
+    fn handle_view_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        if ctx.input_mut(|input| input.consume_key(egui::Modifiers::COMMAND, egui::Key::H)) {
+            self.toggle_hidden();
+        }
+    }
+
+    /// ctrl+f opens the quick filter and escape closes it.
+    /// typing into the listing jumps to the item starting with the typed text, or opens the filter if the settings say so
+    fn handle_filter_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let filtering = self.tab().filter.is_some();
+        let (open, typed, close) = ctx.input_mut(|input| {
+            let open = input.consume_key(egui::Modifiers::COMMAND, egui::Key::F);
+            let typed: String = input.events.iter()
+                .filter_map(|event| match event {
+                    egui::Event::Text(text) => Some(text.as_str()),
+                    _ => None
+                })
+                .collect();
+            let close = filtering && input.consume_key(egui::Modifiers::NONE, egui::Key::Escape);
+            (open, typed, close)
+        });
+        let typing_filters = self.settings.typing_filters;
+        let tab = self.tab_mut();
+        if close {
+            tab.set_filter(None);
+        }
+        else if !open && !typing_filters && tab.filter.is_none() {
+            if typed.is_empty() || (typed.trim().is_empty() && !tab.type_ahead.is_typing()) {
+                return;
+            }
+            if let Some(index) = tab.type_ahead.type_text(&typed, &tab.child_directories, tab.selection.focus) {
+                tab.selection.click(&mut tab.child_directories, index, egui::Modifiers::NONE);
+                tab.file_list.scroll_to(index);
+            }
+        }
+        else if open || !typed.trim().is_empty() {
+            // the field gets the focus right away and would insert the typed text a second time
+            ctx.input_mut(|input| input.events.retain(|event| !matches!(event, egui::Event::Text(_))));
+            let mut query = tab.filter.clone().unwrap_or_default();
+            query.push_str(&typed);
+            let end = egui::text::CCursor::new(query.chars().count());
+            tab.set_filter(Some(query));
+            let id = tab.id.with("filter");
+            let mut state = egui::text_edit::TextEditState::load(ctx, id).unwrap_or_default();
+            state.set_ccursor_range(Some(egui::text_edit::CCursorRange::one(end)));
+            state.store(ctx, id);
+            ctx.memory_mut(|memory| memory.request_focus(id));
+        }
+    }
+
+    fn handle_tab_shortcuts(&mut self, ctx: &egui::Context) {
+        let (new_tab, close_tab, next_tab, previous_tab) = ctx.input_mut(|input| {
+            let new_tab = input.consume_key(egui::Modifiers::COMMAND, egui::Key::T);
+            let close_tab = input.consume_key(egui::Modifiers::COMMAND, egui::Key::W);
+            let next_tab = input.consume_key(egui::Modifiers::COMMAND, egui::Key::Tab);
+            let previous_tab = input.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Tab);
+            (new_tab, close_tab, next_tab, previous_tab)
+        });
+        if new_tab {
+            self.open_tab(self.tab().directory.clone());
+        }
+        let pane = self.pane_mut();
+        if close_tab {
+            pane.close_tab(pane.active_tab);
+        }
+        if next_tab {
+            pane.next_tab();
+        }
+        if previous_tab {
+            pane.previous_tab();
+        }
+    }
+
+    fn handle_clipboard_shortcuts(&mut self, ctx: &egui::Context) {
+        // text fields use these shortcuts for their own text
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (copy, cut, paste) = ctx.input_mut(|input| {
+            let copy = input.consume_key(egui::Modifiers::COMMAND, egui::Key::C);
+            let cut = input.consume_key(egui::Modifiers::COMMAND, egui::Key::X);
+            let paste = input.consume_key(egui::Modifiers::COMMAND, egui::Key::V);
+            (copy, cut, paste)
+        });
+        if copy {
+            self.copy_selection_to_clipboard(clipboard::ClipboardMode::Copy);
+        }
+        if cut {
+            self.copy_selection_to_clipboard(clipboard::ClipboardMode::Cut);
+        }
+        if paste {
+            self.paste();
+        }
+    }
+
+    fn handle_delete_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (trash, delete) = ctx.input_mut(|input| {
+            let trash = input.consume_key(egui::Modifiers::NONE, egui::Key::Delete);
+            let delete = input.consume_key(egui::Modifiers::SHIFT, egui::Key::Delete);
+            (trash, delete)
+        });
+        let selected = self.pane().selected_paths();
+        if selected.is_empty() {
+            return;
+        }
+        if trash {
+            self.jobs.push(jobs::JobKind::Trash(selected));
+        }
+        else if delete {
+            if let Err(error) = self.try_delete_items(selected) {
+                self.report_error(error.to_string());
+            }
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(operation) = self.journal.pop_undo() {
+            self.jobs.push(jobs::JobKind::Undo(operation));
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(operation) = self.journal.pop_redo() {
+            self.jobs.push(jobs::JobKind::Redo(operation));
+        }
+    }
+
+    fn handle_journal_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (undo, redo) = ctx.input_mut(|input| {
+            let undo = input.consume_key(egui::Modifiers::COMMAND, egui::Key::Z);
+            let redo = input.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z)
+                || input.consume_key(egui::Modifiers::COMMAND, egui::Key::Y);
+            (undo, redo)
+        });
+        if undo {
+            self.undo();
+        }
+        if redo {
+            self.redo();
+        }
+    }
+
+    fn handle_trash_action(&mut self, action: trash_view::TrashAction) {
+        match action {
+            trash_view::TrashAction::Restore(items) => self.jobs.push(jobs::JobKind::Restore(items)),
+            trash_view::TrashAction::Delete(items) => self.purge_dialog.get_or_insert_with(Vec::new).extend(items)
+        }
+    }
+
+    fn handle_pane_shortcuts(&mut self, ctx: &egui::Context) {
+        let (copy, move_) = ctx.input_mut(|input| {
+            let copy = input.consume_key(egui::Modifiers::NONE, egui::Key::F5);
+            let move_ = input.consume_key(egui::Modifiers::NONE, egui::Key::F6);
+            (copy, move_)
+        });
+        if copy {
+            self.transfer_to_other_pane(Transfer::Copy);
+        }
+        if move_ {
+            self.transfer_to_other_pane(Transfer::Move);
+        }
+    }
+
+    fn handle_navigation_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.input_mut(|input| input.consume_key(egui::Modifiers::COMMAND, egui::Key::L)) {
+            self.edit_location(self.active_pane);
+        }
+        let (back, forward, up) = ctx.input_mut(|input| {
+            let back = input.consume_key(egui::Modifiers::ALT, egui::Key::ArrowLeft) || input.pointer.button_pressed(egui::PointerButton::Extra1);
+            let forward = input.consume_key(egui::Modifiers::ALT, egui::Key::ArrowRight) || input.pointer.button_pressed(egui::PointerButton::Extra2);
+            let up = input.consume_key(egui::Modifiers::ALT, egui::Key::ArrowUp);
+            (back, forward, up)
+        });
+        // text fields use backspace for their own text
+        let parent = !ctx.wants_keyboard_input() && ctx.input_mut(|input| input.consume_key(egui::Modifiers::NONE, egui::Key::Backspace));
+        let tab = self.tab_mut();
+        let result = if back {
+            tab.go_back(1)
+        }
+        else if forward {
+            tab.go_forward(1)
+        }
+        else if up || parent {
+            tab.go_up()
+        }
+        else {
+            Ok(())
+        };
+        if let Err(message) = result {
+            self.report_error(message);
+        }
+    }
+
+    fn navigation_buttons(&mut self, ui: &mut egui::Ui, pane_index: usize) {
+        let tab = self.panes[pane_index].tab_mut();
+        let back = ui.add_enabled(tab.history.can_go_back(), egui::Button::new("⬅"))
+            .on_hover_text("Back (Alt+Left)");
+        let back_steps = widgets::history_menu(back.clone(), tab.history.back_entries());
+        let forward = ui.add_enabled(tab.history.can_go_forward(), egui::Button::new("➡"))
+            .on_hover_text("Forward (Alt+Right)");
+        let forward_steps = widgets::history_menu(forward.clone(), tab.history.forward_entries());
+        let up = ui.add_enabled(tab.directory.parent().is_some(), egui::Button::new("⬆"))
+            .on_hover_text("Up (Alt+Up)");
+
+        let result = if back.clicked() {
+            tab.go_back(1)
+        }
+        else if let Some(steps) = back_steps {
+            tab.go_back(steps)
+        }
+        else if forward.clicked() {
+            tab.go_forward(1)
+        }
+        else if let Some(steps) = forward_steps {
+            tab.go_forward(steps)
+        }
+        else if up.clicked() {
+            tab.go_up()
+        }
+        else {
+            Ok(())
+        };
+        if let Err(message) = result {
+            self.report_error(message);
+        }
+    }
+
+    fn show_pane(&mut self, ui: &mut egui::Ui, pane_index: usize) {
+        let active = self.dual_pane && pane_index == self.active_pane;
+        let stroke = if active { ui.visuals().selection.stroke } else { egui::Stroke::NONE };
+        let frame = egui::Frame::none().stroke(stroke).inner_margin(egui::Margin::same(2.0));
+        let response = frame.show(ui, |ui| {
+            let pane = &mut self.panes[pane_index];
+            let tabs: Vec<(egui::Id, String)> = pane.tabs.iter().map(|tab| (tab.id, tab.title())).collect();
+            match widgets::tab_bar(ui, &tabs, pane.active_tab) {
+                Some(widgets::TabBarAction::Select(index)) => pane.active_tab = index,
+                Some(widgets::TabBarAction::Close(index)) => pane.close_tab(index),
+                Some(widgets::TabBarAction::Move(from, to)) => pane.move_tab(from, to),
+                Some(widgets::TabBarAction::New) => {
+                    let directory = pane.tab().directory.clone();
+                    match self.new_tab(directory) {
+                        Ok(tab) => self.panes[pane_index].open_tab(tab),
+                        Err(message) => self.report_error(message)
                     }
                 },
-                watcher::Change::Modify(_) => {},
+                None => {}
             }
-        }
-        
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.set_enabled(self.delete_dialog.is_none() && self.error_dialogs.is_empty());
 
             let width = ui.available_width();
-            if let Some(path) = widgets::path_navigation_bar(ui, &self.directory, width) {
-                if let Err(message) = self.change_dir(path) {
-                    self.report_error(message);
+            let tab = self.panes[pane_index].tab_mut();
+            if let Some(mut text) = tab.location.take() {
+                match widgets::location_field(ui, &mut text, tab.id.with("location"), &tab.directory, width) {
+                    Some(widgets::LocationAction::Submit(text)) => self.open_location(pane_index, &text),
+                    Some(widgets::LocationAction::Cancel) => {},
+                    None => tab.location = Some(text)
+                }
+            }
+            else {
+                match widgets::path_navigation_bar(ui, &tab.directory, width) {
+                    Some(widgets::PathBarAction::Navigate(path)) => {
+                        if let Err(message) = tab.change_dir(path) {
+                            self.report_error(message);
+                        }
+                    },
+                    Some(widgets::PathBarAction::Edit) => self.edit_location(pane_index),
+                    None => {}
                 }
             }
             ui.horizontal(|ui| {
+                self.navigation_buttons(ui, pane_index);
+                ui.separator();
                 if ui.button("new folder").clicked() {
-                    self.file_list.new_item(file_list::ItemKind::Directory);
+                    self.panes[pane_index].tab_mut().file_list.new_item(file_list::ItemKind::Directory);
                 }
                 if ui.button("new file").clicked() {
-                    self.file_list.new_item(file_list::ItemKind::File);
+                    self.panes[pane_index].tab_mut().file_list.new_item(file_list::ItemKind::File);
+                }
+                ui.separator();
+                let file_list = &mut self.panes[pane_index].tab_mut().file_list;
+                ui.selectable_value(&mut file_list.view_mode, file_list::ViewMode::List, "list");
+                ui.selectable_value(&mut file_list.view_mode, file_list::ViewMode::Details, "details");
+                ui.selectable_value(&mut file_list.view_mode, file_list::ViewMode::Grid, "grid");
+                if file_list.view_mode == file_list::ViewMode::Grid {
+                    egui::ComboBox::from_id_source("icon_size")
+                        .selected_text(file_list.icon_size.title())
+                        .show_ui(ui, |ui| {
+                            for size in file_list::IconSize::ALL {
+                                ui.selectable_value(&mut file_list.icon_size, size, size.title());
+                            }
+                        });
                 }
             });
-            let actions = self.file_list.show(ui, &self.child_directories, &mut self.file_icons_manager);
-            for action in actions {
-                self.handle_action(action);
-            } 
+            let tab = self.panes[pane_index].tab_mut();
+            if let Some(mut query) = tab.filter.clone() {
+                ui.horizontal(|ui| {
+                    ui.label("filter");
+                    let response = ui.add(egui::TextEdit::singleline(&mut query).id(tab.id.with("filter")).hint_text("name"));
+                    let escaped = response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Escape));
+                    let shown = tab.child_directories.iter().filter(|item| item.visible).count();
+                    ui.weak(format!("{shown} of {}", tab.child_directories.len()));
+                    if ui.small_button("×").on_hover_text("Close filter (Escape)").clicked() || escaped {
+                        tab.set_filter(None);
+                    }
+                    else if response.changed() {
+                        tab.set_filter(Some(query));
+                    }
+                });
+            }
+            ui.push_id(tab.id, |ui| {
+                tab.file_list.show(ui, &tab.child_directories, tab.selection.focus, &mut self.file_icons_manager)
+            }).inner
+        });
+
+        if ui.pointer_pressed_at(response.response.rect) {
+            self.active_pane = pane_index;
+        }
+        let actions = response.inner;
+        if !actions.is_empty() {
+            self.active_pane = pane_index;
+        }
+        for action in actions {
+            self.handle_action(action);
+        }
+    }
+
+    /// turns the path bar of the pane into a text field with the current directory in it
+    fn edit_location(&mut self, pane_index: usize) {
+        let tab = self.panes[pane_index].tab_mut();
+        let text = tab.directory.to_string_lossy().to_string();
+        widgets::edit_location(&self.context, tab.id.with("location"), &text);
+        tab.location = Some(text);
+    }
+
+    /// goes to the typed folder, a typed file is shown selected in its folder
+    fn open_location(&mut self, pane_index: usize, text: &str) {
+        let tab = self.panes[pane_index].tab_mut();
+        let path = location::expand(text, &tab.directory);
+        let result = if path.is_dir() {
+            tab.change_dir(path)
+        }
+        else if path.exists() {
+            tab.reveal(&path)
+        }
+        else {
+            Err(format!("{} doesn't exist", path.display()))
+        };
+        if let Err(message) = result {
+            self.report_error(message);
+        }
+    }
+
+    fn handle_search_action(&mut self, action: search_view::SearchAction) {
+        let result = match action {
+            search_view::SearchAction::Open(path) => self.try_open(path),
+            search_view::SearchAction::Reveal(path) => self.tab_mut().reveal(&path),
+        };
+        if let Err(message) = result {
+            self.report_error(message);
+        }
+    }
+
+    fn report_error(&mut self, message: String) {
+        self.error_dialogs.push_front(message);
+    }
+}
+
+impl eframe::App for FileExplorer {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        // the style is set again when the system theme changed or eframe replaced the visuals
+        let dark = self.settings.theme.is_dark(frame.info().system_theme);
+        if ctx.style().visuals.dark_mode != dark || ctx.style().visuals.window_rounding != egui::Rounding::same(WINDOW_ROUNDING) {
+            set_style(ctx, dark);
+        }
+        let mut requests = self.instance.as_ref().map(instance::Server::take_requests).unwrap_or_default();
+        requests.extend(self.file_manager.iter().flat_map(file_manager::FileManagerService::take_requests));
+        for request in requests {
+            self.handle_instance_request(request, frame);
+        }
+
+        let window = &frame.info().window_info;
+        self.settings.window_maximized = window.maximized;
+        if !window.maximized && !window.minimized && !window.fullscreen {
+            self.settings.window_size = Some(window.size.into());
+            self.settings.window_position = window.position.map(Into::into);
+        }
+
+        for pane in &mut self.panes {
+            for directory in pane.update_from_watchers() {
+                self.tree.refresh(&directory);
+            }
+        }
+
+        for error in self.jobs.take_errors() {
+            self.report_error(error);
+        }
+        for record in self.jobs.take_records() {
+            self.journal.apply(record);
+        }
+        self.jobs.remove_finished();
+
+        let dialog_open = self.delete_dialog.is_some() || self.purge_dialog.is_some() || self.bulk_rename.is_some() || !self.error_dialogs.is_empty();
+        if !dialog_open {
+            self.handle_tab_shortcuts(ctx);
+            self.handle_navigation_shortcuts(ctx);
+            self.handle_pane_shortcuts(ctx);
+            self.handle_clipboard_shortcuts(ctx);
+            self.handle_delete_shortcuts(ctx);
+            self.handle_journal_shortcuts(ctx);
+            self.handle_selection_shortcuts(ctx);
+            self.handle_item_shortcuts(ctx);
+            self.handle_view_shortcuts(ctx);
+            self.handle_filter_shortcuts(ctx);
+        }
+
+        egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
+            ui.set_enabled(!dialog_open);
+            ui.horizontal(|ui| {
+                let mut dual_pane = self.dual_pane;
+                if ui.toggle_value(&mut dual_pane, "split view").changed() {
+                    self.set_dual_pane(dual_pane);
+                }
+                ui.toggle_value(&mut self.show_sidebar, "sidebar");
+                ui.toggle_value(&mut self.show_journal, "history");
+                ui.toggle_value(&mut self.show_preview, "preview");
+                let mut show_hidden = self.ignore.show_hidden;
+                if ui.toggle_value(&mut show_hidden, "hidden files").on_hover_text("Ctrl+H").changed() {
+                    self.toggle_hidden();
+                }
+                ui.menu_button("ignore", |ui| {
+                    ui.label("patterns");
+                    let response = ui.add(egui::TextEdit::singleline(&mut self.ignore_patterns).hint_text("*.pyc, node_modules, .git"));
+                    let mut ignore = self.ignore.clone();
+                    let changed = ui.checkbox(&mut ignore.use_gitignore, "use .gitignore").changed();
+                    if response.lost_focus() && self.ignore_patterns != ignore.patterns {
+                        match ignore.set_patterns(self.ignore_patterns.clone()) {
+                            Ok(()) => self.set_ignore(ignore),
+                            Err(error) => self.report_error(error)
+                        }
+                    }
+                    else if changed {
+                        self.set_ignore(ignore);
+                    }
+                });
+                let mut show_search = self.search_view.is_some();
+                if ui.toggle_value(&mut show_search, "search").changed() {
+                    self.search_view = if show_search { Some(search_view::SearchView::new()) } else { None };
+                }
+                let mut show_trash = self.trash_view.is_some();
+                if ui.toggle_value(&mut show_trash, "trash").changed() {
+                    self.trash_view = if show_trash { Some(trash_view::TrashView::new()) } else { None };
+                }
+                ui.toggle_value(&mut self.show_settings, "settings");
+                if self.dual_pane {
+                    ui.separator();
+                    if ui.button("copy to other pane").on_hover_text("F5").clicked() {
+                        self.transfer_to_other_pane(Transfer::Copy);
+                    }
+                    if ui.button("move to other pane").on_hover_text("F6").clicked() {
+                        self.transfer_to_other_pane(Transfer::Move);
+                    }
+                }
+            });
+        });
+
+        if !self.jobs.is_empty() {
+            egui::TopBottomPanel::bottom("jobs").resizable(true).show(ctx, |ui| {
+                if widgets::jobs_panel(ui, &self.jobs) {
+                    self.jobs.clear_done();
+                }
+            });
+        }
+
+        if self.show_preview {
+            // the focused item is previewed as long as it is still selected
+            let tab = self.panes[self.active_pane].tab();
+            let item = tab.selection.focus
+                .and_then(|index| tab.child_directories.get(index))
+                .filter(|item| item.selected);
+            self.preview.set_item(item);
+            egui::SidePanel::right("preview").resizable(true).default_width(300.0).show(ctx, |ui| {
+                self.preview.show(ui);
+            });
+        }
+
+        let directory = self.panes[self.active_pane].tab().directory.clone();
+        self.places.visit(&directory);
+        if self.show_sidebar {
+            egui::SidePanel::left("sidebar").resizable(true).default_width(200.0).show(ctx, |ui| {
+                ui.set_enabled(!dialog_open);
+                let place = egui::ScrollArea::vertical()
+                    .id_source("places")
+                    .max_height(ui.available_height() / 2.0)
+                    .show(ui, |ui| self.places.show(ui, &directory))
+                    .inner;
+                ui.separator();
+                let folder = self.tree.show(ui, &directory);
+                if let Some(path) = place.or(folder) {
+                    if let Err(message) = self.tab_mut().change_dir(path) {
+                        self.report_error(message);
+                    }
+                }
+            });
+        }
+        for error in self.places.take_errors() {
+            self.report_error(error);
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.set_enabled(!dialog_open);
+            if self.dual_pane {
+                ui.columns(2, |columns| {
+                    self.show_pane(&mut columns[0], 0);
+                    self.show_pane(&mut columns[1], 1);
+                });
+            }
+            else {
+                self.show_pane(ui, 0);
+            }
         });
 
+        if self.show_journal {
+            match widgets::journal_window(ctx, &mut self.show_journal, &self.journal) {
+                Some(widgets::JournalAction::Undo) => self.undo(),
+                Some(widgets::JournalAction::Redo) => self.redo(),
+                None => {}
+            }
+        }
+
+        if self.show_settings {
+            let previous = self.settings.clone();
+            if widgets::settings_window(ctx, &mut self.show_settings, &mut self.settings) {
+                self.apply_settings(&previous);
+            }
+        }
+
+        if let Some(trash_view) = &mut self.trash_view {
+            let mut action = None;
+            if !trash_view.show(ctx, &mut action) {
+                self.trash_view = None;
+            }
+            if let Some(action) = action {
+                self.handle_trash_action(action);
+            }
+        }
+
+        if let Some(search_view) = &mut self.search_view {
+            let mut action = None;
+            let directory = self.panes[self.active_pane].tab().directory.clone();
+            if !search_view.show(ctx, &directory, &mut action) {
+                self.search_view = None;
+            }
+            if let Some(action) = action {
+                self.handle_search_action(action);
+            }
+        }
+
         if let Some(message) = self.error_dialogs.back() {
             if widgets::error_dialog(ctx, message) {
                 self.error_dialogs.pop_back();
@@ -223,6 +1318,14 @@ impl eframe::App for FileExplorer {
         }
         else {
             self.update_delete_dialog(ctx);
+            self.update_purge_dialog(ctx);
+            self.update_bulk_rename_dialog(ctx);
         }
     }
+
+    fn on_close_event(&mut self) -> bool {
+        // there is no window left to show an error in
+        let _ = self.save_settings();
+        true
+    }
 }
\ No newline at end of file
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+/// what the details view shows about an item, read once when the listing is refreshed instead of every frame
+#[derive(Clone)]
+pub struct ItemMetadata {
+    pub is_dir: bool,
+    pub is_symlink: bool,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    pub created: Option<SystemTime>,
+    pub accessed: Option<SystemTime>,
+    pub mode: u32,
+    pub uid: u32,
+    pub gid: u32
+}
+
+impl ItemMetadata {
+    pub fn read(path: &Path) -> Option<Self> {
+        let link = fs::symlink_metadata(path).ok()?;
+        let is_symlink = link.file_type().is_symlink();
+        // a symlink is described by what it points to, unless it is broken
+        let metadata = if is_symlink { fs::metadata(path).unwrap_or(link) } else { link };
+        Some(Self {
+            is_dir: metadata.is_dir(),
+            is_symlink,
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            created: metadata.created().ok(),
+            accessed: metadata.accessed().ok(),
+            mode: metadata.mode(),
+            uid: metadata.uid(),
+            gid: metadata.gid()
+        })
+    }
+
+    pub fn type_name(&self, path: &Path) -> String {
+        let name = if self.is_dir {
+            String::from("folder")
+        }
+        else {
+            match path.extension() {
+                Some(extension) => format!("{} file", extension.to_string_lossy().to_uppercase()),
+                None => String::from("file")
+            }
+        };
+        if self.is_symlink {
+            format!("link to {name}")
+        }
+        else {
+            name
+        }
+    }
+
+    /// the permissions the way `ls -l` shows them, like `drwxr-xr-x`
+    pub fn permissions(&self) -> String {
+        let mut text = String::with_capacity(10);
+        text.push(if self.is_symlink { 'l' } else if self.is_dir { 'd' } else { '-' });
+        let bits = [(0o400, 'r'), (0o200, 'w'), (0o100, 'x'), (0o40, 'r'), (0o20, 'w'), (0o10, 'x'), (0o4, 'r'), (0o2, 'w'), (0o1, 'x')];
+        for (bit, letter) in bits {
+            text.push(if self.mode & bit != 0 { letter } else { '-' });
+        }
+        text
+    }
+
+    pub fn owner(&self) -> String {
+        static USERS: OnceLock<HashMap<u32, String>> = OnceLock::new();
+        let users = USERS.get_or_init(|| read_id_names("/etc/passwd"));
+        users.get(&self.uid).cloned().unwrap_or_else(|| self.uid.to_string())
+    }
+
+    pub fn group(&self) -> String {
+        static GROUPS: OnceLock<HashMap<u32, String>> = OnceLock::new();
+        let groups = GROUPS.get_or_init(|| read_id_names("/etc/group"));
+        groups.get(&self.gid).cloned().unwrap_or_else(|| self.gid.to_string())
+    }
+}
+
+pub fn format_time(time: Option<SystemTime>) -> String {
+    match time {
+        Some(time) => chrono::DateTime::<chrono::Local>::from(time).format("%Y-%m-%d %H:%M").to_string(),
+        None => String::new()
+    }
+}
+
+/// reads the names out of /etc/passwd or /etc/group, both have the name first and the id third
+fn read_id_names(path: &str) -> HashMap<u32, String> {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    content.lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let id = fields.nth(1)?.parse().ok()?;
+            Some((id, name.to_owned()))
+        })
+        .collect()
+}
@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use crate::tab::Tab;
+
+pub struct Pane {
+    pub tabs: Vec<Tab>,
+    pub active_tab: usize
+}
+
+impl Pane {
+    pub fn new(tab: Tab) -> Self {
+        Self {
+            tabs: vec![tab],
+            active_tab: 0
+        }
+    }
+
+    pub fn tab(&self) -> &Tab {
+        &self.tabs[self.active_tab]
+    }
+
+    pub fn tab_mut(&mut self) -> &mut Tab {
+        &mut self.tabs[self.active_tab]
+    }
+
+    /// adds the tab after the active one
+    pub fn open_tab(&mut self, tab: Tab) {
+        self.active_tab += 1;
+        self.tabs.insert(self.active_tab, tab);
+    }
+
+    pub fn close_tab(&mut self, index: usize) {
+        // there is always at least one tab open
+        if self.tabs.len() <= 1 {
+            return;
+        }
+        self.tabs.remove(index);
+        if self.active_tab > index || self.active_tab == self.tabs.len() {
+            self.active_tab -= 1;
+        }
+    }
+
+    pub fn move_tab(&mut self, from: usize, to: usize) {
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+        if self.active_tab == from {
+            self.active_tab = to;
+        }
+        else if from < self.active_tab && to >= self.active_tab {
+            self.active_tab -= 1;
+        }
+        else if from > self.active_tab && to <= self.active_tab {
+            self.active_tab += 1;
+        }
+    }
+
+    pub fn next_tab(&mut self) {
+        self.active_tab = (self.active_tab + 1) % self.tabs.len();
+    }
+
+    pub fn previous_tab(&mut self) {
+        self.active_tab = (self.active_tab + self.tabs.len() - 1) % self.tabs.len();
+    }
+
+    /// returns the directories the folders of which may have changed
+    pub fn update_from_watchers(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for tab in &mut self.tabs {
+            if tab.update_from_watcher() {
+                changed.push(tab.directory.clone());
+            }
+        }
+        changed
+    }
+
+    pub fn selected_paths(&self) -> Vec<PathBuf> {
+        self.tab().child_directories.iter()
+            .filter(|item| item.selected)
+            .map(|item| item.path.clone())
+            .collect()
+    }
+}
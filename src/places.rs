@@ -0,0 +1,335 @@
+use std::collections::VecDeque;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::file_list;
+use crate::settings;
+use crate::trash;
+
+const MAX_RECENT: usize = 10;
+/// the mount table is read again after this long, there is no notification for it
+const MOUNTS_REFRESH: Duration = Duration::from_secs(5);
+/// filesystems that are parts of the system rather than volumes someone would open
+const SYSTEM_MOUNT_PREFIXES: [&str; 3] = ["/boot", "/snap", "/var/snap"];
+
+pub struct Place {
+    pub path: PathBuf,
+    pub name: String
+}
+
+impl Place {
+    fn new(path: PathBuf) -> Self {
+        let name = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().to_string();
+        Self { path, name }
+    }
+}
+
+/// the sidebar: home and the XDG user folders, bookmarks, mounted filesystems and the recently visited folders
+pub struct Places {
+    standard: Vec<Place>,
+    bookmarks: Vec<Place>,
+    mounts: Vec<Place>,
+    mounts_read: Instant,
+    recent: VecDeque<PathBuf>,
+    /// the bookmark being renamed and its new name
+    renaming: Option<(usize, String)>,
+    errors: Vec<String>
+}
+
+impl Places {
+    pub fn new() -> Self {
+        let mut errors = Vec::new();
+        let bookmarks = match bookmarks_file() {
+            Some(file) => read_bookmarks(&file).unwrap_or_else(|error| {
+                if error.kind() != io::ErrorKind::NotFound {
+                    errors.push(format!("Couldn't read the bookmarks: {error}"));
+                }
+                Vec::new()
+            }),
+            None => Vec::new()
+        };
+        Self {
+            standard: standard_places(),
+            bookmarks,
+            mounts: mounts(),
+            mounts_read: Instant::now(),
+            recent: VecDeque::new(),
+            renaming: None,
+            errors
+        }
+    }
+
+    pub fn take_errors(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// remembers the folder as the most recently visited one
+    pub fn visit(&mut self, path: &Path) {
+        if self.recent.front().map(|recent| recent == path).unwrap_or(false) {
+            return;
+        }
+        self.recent.retain(|recent| recent != path);
+        self.recent.push_front(path.to_path_buf());
+        self.recent.truncate(MAX_RECENT);
+    }
+
+    pub fn add_bookmark(&mut self, path: PathBuf) {
+        if self.bookmarks.iter().any(|bookmark| bookmark.path == path) {
+            return;
+        }
+        self.bookmarks.push(Place::new(path));
+        self.save_bookmarks();
+    }
+
+    /// adds the GTK bookmarks that aren't bookmarked yet
+    pub fn import_gtk_bookmarks(&mut self) {
+        let Some(home) = env::var_os("HOME") else {
+            return;
+        };
+        match read_bookmarks(&PathBuf::from(home).join(".config/gtk-3.0/bookmarks")) {
+            Ok(imported) => {
+                for place in imported {
+                    if !self.bookmarks.iter().any(|bookmark| bookmark.path == place.path) {
+                        self.bookmarks.push(place);
+                    }
+                }
+                self.save_bookmarks();
+            },
+            Err(error) => self.errors.push(format!("Couldn't import the GTK bookmarks: {error}"))
+        }
+    }
+
+    fn save_bookmarks(&mut self) {
+        let Some(file) = bookmarks_file() else {
+            return;
+        };
+        if let Err(error) = write_bookmarks(&file, &self.bookmarks) {
+            self.errors.push(format!("Couldn't save the bookmarks: {error}"));
+        }
+    }
+
+    /// returns the place that was clicked
+    pub fn show(&mut self, ui: &mut egui::Ui, current: &Path) -> Option<PathBuf> {
+        if self.mounts_read.elapsed() > MOUNTS_REFRESH {
+            self.mounts = mounts();
+            self.mounts_read = Instant::now();
+        }
+        let mut clicked = None;
+        egui::CollapsingHeader::new("places").default_open(true).show(ui, |ui| {
+            for place in &self.standard {
+                place_label(ui, place, current, &mut clicked);
+            }
+        });
+        self.show_bookmarks(ui, current, &mut clicked);
+        if !self.mounts.is_empty() {
+            egui::CollapsingHeader::new("devices").default_open(true).show(ui, |ui| {
+                for place in &self.mounts {
+                    place_label(ui, place, current, &mut clicked);
+                }
+            });
+        }
+        egui::CollapsingHeader::new("recent").default_open(false).show(ui, |ui| {
+            for path in self.recent.iter().filter(|path| *path != current) {
+                place_label(ui, &Place::new(path.clone()), current, &mut clicked);
+            }
+        });
+        clicked
+    }
+
+    /// folders dragged out of a listing and dropped on the section are bookmarked
+    fn show_bookmarks(&mut self, ui: &mut egui::Ui, current: &Path, clicked: &mut Option<PathBuf>) {
+        let mut moved = None;
+        let mut removed = None;
+        let mut renamed = None;
+        let section = egui::CollapsingHeader::new("bookmarks").default_open(true).show(ui, |ui| {
+            if self.bookmarks.is_empty() {
+                ui.weak("drop folders here");
+            }
+            for (index, place) in self.bookmarks.iter().enumerate() {
+                if let Some((renaming, name)) = self.renaming.as_mut().filter(|(renaming, _)| *renaming == index) {
+                    let response = ui.text_edit_singleline(name);
+                    response.request_focus();
+                    if response.lost_focus() {
+                        let cancelled = ui.input(|input| input.key_pressed(egui::Key::Escape));
+                        renamed = Some((*renaming, (!cancelled && !name.trim().is_empty()).then(|| name.trim().to_owned())));
+                    }
+                    continue;
+                }
+                let response = place_label(ui, place, current, clicked);
+                response.context_menu(|ui| {
+                    if ui.button("rename").clicked() {
+                        self.renaming = Some((index, place.name.clone()));
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(index > 0, egui::Button::new("move up")).clicked() {
+                        moved = Some((index, index - 1));
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(index + 1 < self.bookmarks.len(), egui::Button::new("move down")).clicked() {
+                        moved = Some((index, index + 1));
+                        ui.close_menu();
+                    }
+                    if ui.button("remove").clicked() {
+                        removed = Some(index);
+                        ui.close_menu();
+                    }
+                });
+            }
+        });
+        let mut area = section.header_response.rect;
+        if let Some(body) = &section.body_response {
+            area = area.union(body.rect);
+        }
+        section.header_response.context_menu(|ui| {
+            if ui.button("bookmark this folder").clicked() {
+                self.add_bookmark(current.to_path_buf());
+                ui.close_menu();
+            }
+            if ui.button("import GTK bookmarks").clicked() {
+                self.import_gtk_bookmarks();
+                ui.close_menu();
+            }
+        });
+
+        if file_list::dragged_path(ui.ctx()).is_some() && ui.rect_contains_pointer(area) {
+            ui.painter().rect_stroke(area, egui::Rounding::none(), ui.visuals().selection.stroke);
+            if ui.input(|input| input.pointer.any_released()) {
+                if let Some(path) = file_list::take_dragged_path(ui.ctx()).filter(|path| path.is_dir()) {
+                    self.add_bookmark(path);
+                }
+            }
+        }
+
+        if let Some((index, name)) = renamed {
+            if let Some(name) = name {
+                self.bookmarks[index].name = name;
+                self.save_bookmarks();
+            }
+            self.renaming = None;
+        }
+        if let Some((from, to)) = moved {
+            self.bookmarks.swap(from, to);
+            self.save_bookmarks();
+        }
+        if let Some(index) = removed {
+            self.bookmarks.remove(index);
+            self.save_bookmarks();
+        }
+    }
+}
+
+fn place_label(ui: &mut egui::Ui, place: &Place, current: &Path, clicked: &mut Option<PathBuf>) -> egui::Response {
+    let response = ui.selectable_label(place.path == current, &place.name).on_hover_text(place.path.to_string_lossy());
+    if response.clicked() {
+        *clicked = Some(place.path.clone());
+    }
+    response
+}
+
+/// home and the XDG user folders that exist, named like the folders themselves
+fn standard_places() -> Vec<Place> {
+    let Some(home) = env::var_os("HOME").map(PathBuf::from) else {
+        return Vec::new();
+    };
+    let user_dirs = fs::read_to_string(home.join(".config/user-dirs.dirs")).unwrap_or_default();
+    let user_dir = |key: &str, fallback: &str| {
+        user_dirs.lines()
+            .filter_map(|line| line.trim().strip_prefix(key)?.strip_prefix('='))
+            .map(|value| value.trim_matches('"'))
+            .map(|value| match value.strip_prefix("$HOME") {
+                Some(relative) => home.join(relative.trim_start_matches('/')),
+                None => PathBuf::from(value)
+            })
+            .next_back()
+            .unwrap_or_else(|| home.join(fallback))
+    };
+    let mut places = vec![Place { path: home.clone(), name: String::from("home") }];
+    let keys = [
+        ("XDG_DESKTOP_DIR", "Desktop"),
+        ("XDG_DOCUMENTS_DIR", "Documents"),
+        ("XDG_DOWNLOAD_DIR", "Downloads"),
+        ("XDG_MUSIC_DIR", "Music"),
+        ("XDG_PICTURES_DIR", "Pictures"),
+        ("XDG_VIDEOS_DIR", "Videos")
+    ];
+    for (key, fallback) in keys {
+        let path = user_dir(key, fallback);
+        // a user folder that is set to home itself means it is disabled
+        if path != home && path.is_dir() {
+            places.push(Place::new(path));
+        }
+    }
+    places
+}
+
+/// the mounted filesystems that are backed by a device, from `/proc/self/mountinfo`
+fn mounts() -> Vec<Place> {
+    let Ok(mountinfo) = fs::read_to_string("/proc/self/mountinfo") else {
+        return Vec::new();
+    };
+    let mut places: Vec<Place> = Vec::new();
+    for line in mountinfo.lines() {
+        // the optional fields end with a lone dash, the filesystem type and the source come after it
+        let Some((fields, filesystem)) = line.split_once(" - ") else {
+            continue;
+        };
+        let Some(mount_point) = fields.split_whitespace().nth(4).map(trash::unescape_mount_point) else {
+            continue;
+        };
+        let source = filesystem.split_whitespace().nth(1).unwrap_or_default();
+        let system = SYSTEM_MOUNT_PREFIXES.iter().any(|prefix| mount_point.starts_with(prefix));
+        if !source.starts_with("/dev/") || system || places.iter().any(|place| place.path == mount_point) {
+            continue;
+        }
+        let place = if mount_point == Path::new("/") {
+            Place { path: mount_point, name: String::from("file system") }
+        }
+        else {
+            Place::new(mount_point)
+        };
+        places.push(place);
+    }
+    places
+}
+
+/// the bookmarks are written like the GTK ones, a `file://` uri per line followed by an optional name
+fn bookmarks_file() -> Option<PathBuf> {
+    Some(settings::config_dir()?.join("bookmarks"))
+}
+
+fn read_bookmarks(file: &Path) -> io::Result<Vec<Place>> {
+    let content = fs::read_to_string(file)?;
+    let places = content.lines()
+        .filter_map(|line| {
+            let (uri, name) = line.split_once(' ').unwrap_or((line, ""));
+            let path = trash::path_from_uri(uri)?;
+            let mut place = Place::new(path);
+            if !name.trim().is_empty() {
+                place.name = name.trim().to_owned();
+            }
+            Some(place)
+        })
+        .collect();
+    Ok(places)
+}
+
+fn write_bookmarks(file: &Path, bookmarks: &[Place]) -> io::Result<()> {
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut content = String::new();
+    for bookmark in bookmarks {
+        content.push_str("file://");
+        content.push_str(&trash::encode_path(&bookmark.path));
+        // the name is only written when it was changed from the name of the folder
+        if Place::new(bookmark.path.clone()).name != bookmark.name {
+            content.push(' ');
+            content.push_str(&bookmark.name);
+        }
+        content.push('\n');
+    }
+    fs::write(file, content)
+}
@@ -0,0 +1,259 @@
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::SystemTime;
+
+use crate::file_list::FileListItem;
+use crate::gui_extension::UiHelpersExt;
+use crate::highlight;
+use crate::metadata::{self, ItemMetadata};
+use crate::thumbnails;
+use crate::widgets;
+
+const TEXT_BYTES: u64 = 64 * 1024;
+const HEX_BYTES: usize = 16 * 1024;
+const HEX_ROW_BYTES: usize = 16;
+/// images are scaled down to this before they are sent to the ui, the preview is never bigger
+const IMAGE_MAX_SIDE: u32 = 1024;
+
+enum Content {
+    Text { text: String, truncated: bool },
+    Image { image: egui::ColorImage, width: u32, height: u32 },
+    Binary { bytes: Vec<u8>, truncated: bool },
+    Directory { items: usize },
+    Error(String)
+}
+
+struct Shared {
+    /// only the latest request is kept, the ones skipped over while scrolling are never loaded
+    request: Mutex<Option<PathBuf>>,
+    available: Condvar,
+    result: Mutex<Option<(PathBuf, Content)>>
+}
+
+struct Preview {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    metadata: Option<ItemMetadata>,
+    content: Option<Content>,
+    text: Option<Arc<egui::Galley>>,
+    image: Option<egui_extras::RetainedImage>
+}
+
+/// the panel that shows the content of the focused file, loaded on a background thread
+pub struct PreviewPanel {
+    shared: Arc<Shared>,
+    preview: Option<Preview>
+}
+
+impl PreviewPanel {
+    pub fn new(ctx: egui::Context) -> Self {
+        let shared = Arc::new(Shared {
+            request: Mutex::new(None),
+            available: Condvar::new(),
+            result: Mutex::new(None)
+        });
+        let worker_shared = shared.clone();
+        thread::spawn(move || worker(&worker_shared, &ctx));
+        Self {
+            shared,
+            preview: None
+        }
+    }
+
+    /// previews the item, the content is loaded again only when it is another file or it was modified
+    pub fn set_item(&mut self, item: Option<&FileListItem>) {
+        let Some(item) = item else {
+            self.preview = None;
+            return;
+        };
+        let modified = item.metadata.as_ref().and_then(|metadata| metadata.modified);
+        if let Some(preview) = &self.preview {
+            if preview.path == item.path && preview.modified == modified {
+                return;
+            }
+        }
+        self.preview = Some(Preview {
+            path: item.path.clone(),
+            modified,
+            metadata: item.metadata.clone(),
+            content: None,
+            text: None,
+            image: None
+        });
+        *self.shared.request.lock().unwrap() = Some(item.path.clone());
+        self.shared.available.notify_one();
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        self.receive();
+        let Some(preview) = &mut self.preview else {
+            ui.centered_and_justified(|ui| ui.weak("Nothing is selected"));
+            return;
+        };
+
+        ui.add_space(4.0);
+        ui.heading(file_name(&preview.path));
+        if let Some(metadata) = &preview.metadata {
+            egui::Grid::new("preview_metadata").num_columns(2).show(ui, |ui| {
+                let mut row = |name: &str, value: String| {
+                    ui.weak(name);
+                    ui.label(value);
+                    ui.end_row();
+                };
+                row("type", metadata.type_name(&preview.path));
+                if !metadata.is_dir {
+                    row("size", widgets::format_size(metadata.size));
+                }
+                row("modified", metadata::format_time(metadata.modified));
+                row("created", metadata::format_time(metadata.created));
+                row("permissions", metadata.permissions());
+                row("owner", format!("{}:{}", metadata.owner(), metadata.group()));
+                if let Some(Content::Image { width, height, .. }) = &preview.content {
+                    row("dimensions", format!("{width} × {height}"));
+                }
+            });
+        }
+        ui.separator();
+
+        match &preview.content {
+            None => {
+                ui.centered_and_justified(|ui| ui.spinner());
+            },
+            Some(Content::Text { text, truncated }) => {
+                let galley = preview.text.get_or_insert_with(|| {
+                    let extension = preview.path.extension().unwrap_or_default().to_string_lossy();
+                    let job = highlight::highlight(text, &extension, ui.style());
+                    ui.fonts(|fonts| fonts.layout_job(job))
+                });
+                egui::ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
+                    ui.label(galley.clone());
+                    if *truncated {
+                        ui.weak(format!("only the first {} are shown", widgets::format_size(TEXT_BYTES)));
+                    }
+                });
+            },
+            Some(Content::Image { image, .. }) => {
+                let image = preview.image.get_or_insert_with(|| {
+                    egui_extras::RetainedImage::from_color_image(preview.path.to_string_lossy(), image.clone())
+                });
+                let available = ui.available_size();
+                let scale = (available.x / image.size_vec2().x).min(available.y / image.size_vec2().y).min(1.0);
+                ui.vertical_centered(|ui| ui.image_consider_disabled(image, image.size_vec2() * scale));
+            },
+            Some(Content::Binary { bytes, truncated }) => {
+                let row_height = ui.get_text_style_height(egui::TextStyle::Monospace);
+                let rows = bytes.len().div_ceil(HEX_ROW_BYTES);
+                egui::ScrollArea::both().auto_shrink([false, false]).show_rows(ui, row_height, rows, |ui, row_range| {
+                    for row in row_range {
+                        let start = row * HEX_ROW_BYTES;
+                        let end = (start + HEX_ROW_BYTES).min(bytes.len());
+                        ui.add(egui::Label::new(egui::RichText::new(hex_row(start, &bytes[start..end])).monospace()).wrap(false));
+                    }
+                    if *truncated {
+                        ui.weak(format!("only the first {} are shown", widgets::format_size(HEX_BYTES as u64)));
+                    }
+                });
+            },
+            Some(Content::Directory { items }) => {
+                ui.weak(format!("{items} items"));
+            },
+            Some(Content::Error(message)) => {
+                ui.weak(message);
+            }
+        }
+    }
+
+    fn receive(&mut self) {
+        let Some((path, content)) = self.shared.result.lock().unwrap().take() else {
+            return;
+        };
+        if let Some(preview) = &mut self.preview {
+            if preview.path == path {
+                preview.content = Some(content);
+            }
+        }
+    }
+}
+
+fn worker(shared: &Shared, ctx: &egui::Context) {
+    loop {
+        let path = {
+            let mut request = shared.request.lock().unwrap();
+            loop {
+                match request.take() {
+                    Some(path) => break path,
+                    None => request = shared.available.wait(request).unwrap()
+                }
+            }
+        };
+        let content = load(&path).unwrap_or_else(|error| Content::Error(error.to_string()));
+        *shared.result.lock().unwrap() = Some((path, content));
+        ctx.request_repaint();
+    }
+}
+
+fn load(path: &Path) -> std::io::Result<Content> {
+    if path.is_dir() {
+        return Ok(Content::Directory { items: fs::read_dir(path)?.count() });
+    }
+    if thumbnails::is_supported(path) {
+        if let Ok(image) = image::open(path) {
+            let (width, height) = (image.width(), image.height());
+            let image = if width > IMAGE_MAX_SIDE || height > IMAGE_MAX_SIDE {
+                image.thumbnail(IMAGE_MAX_SIDE, IMAGE_MAX_SIDE)
+            }
+            else {
+                image
+            };
+            let image = image.to_rgba8();
+            let size = [image.width() as usize, image.height() as usize];
+            let image = egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw());
+            return Ok(Content::Image { image, width, height });
+        }
+    }
+
+    let mut bytes = Vec::new();
+    fs::File::open(path)?.take(TEXT_BYTES + 1).read_to_end(&mut bytes)?;
+    let truncated = bytes.len() as u64 > TEXT_BYTES;
+    bytes.truncate(TEXT_BYTES as usize);
+    match text_of(&bytes, truncated) {
+        Some(text) => Ok(Content::Text { text, truncated }),
+        None => {
+            let truncated = truncated || bytes.len() > HEX_BYTES;
+            bytes.truncate(HEX_BYTES);
+            Ok(Content::Binary { bytes, truncated })
+        }
+    }
+}
+
+/// the bytes as text when they look like text, a character cut in half at the end of a truncated file is fine
+fn text_of(bytes: &[u8], truncated: bool) -> Option<String> {
+    if bytes.contains(&0) {
+        return None;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Some(text.to_owned()),
+        Err(error) if truncated && error.error_len().is_none() => Some(String::from_utf8_lossy(&bytes[..error.valid_up_to()]).to_string()),
+        Err(_) => None
+    }
+}
+
+/// a line of the hex dump like `hexdump -C` prints it
+fn hex_row(offset: usize, bytes: &[u8]) -> String {
+    let mut hex = String::new();
+    for (index, byte) in bytes.iter().enumerate() {
+        if index == HEX_ROW_BYTES / 2 {
+            hex.push(' ');
+        }
+        hex.push_str(&format!("{byte:02x} "));
+    }
+    let characters: String = bytes.iter().map(|byte| if byte.is_ascii_graphic() || *byte == b' ' { *byte as char } else { '.' }).collect();
+    format!("{offset:08x}  {hex:<49} |{characters}|")
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().to_string()
+}
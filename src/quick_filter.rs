@@ -0,0 +1,120 @@
+use std::time::{Duration, Instant};
+
+use crate::file_list::FileListItem;
+
+/// a pause this long in typing starts the type-ahead over
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// the indices of the characters of `name` that match `query`, ignoring case.
+/// the query as one piece is preferred, otherwise its characters can be spread out in order.
+/// None when the name doesn't match at all
+pub fn match_chars(name: &str, query: &str) -> Option<Vec<usize>> {
+    let name: Vec<char> = name.chars().map(lowercase).collect();
+    let query: Vec<char> = query.chars().map(lowercase).collect();
+    if query.is_empty() {
+        return Some(Vec::new());
+    }
+    if let Some(start) = name.windows(query.len()).position(|window| window == query.as_slice()) {
+        return Some((start..start + query.len()).collect());
+    }
+
+    let mut matched = Vec::with_capacity(query.len());
+    let mut query_chars = query.iter().peekable();
+    for (index, character) in name.iter().enumerate() {
+        if query_chars.peek() == Some(&character) {
+            matched.push(index);
+            query_chars.next();
+        }
+    }
+    if query_chars.peek().is_none() {
+        Some(matched)
+    }
+    else {
+        None
+    }
+}
+
+fn lowercase(character: char) -> char {
+    character.to_lowercase().next().unwrap_or(character)
+}
+
+/// the text with the matched characters underlined and in the strong text color
+pub fn highlighted(text: &str, matched: &[usize], style: &egui::Style, color: egui::Color32) -> egui::text::LayoutJob {
+    let font = egui::TextStyle::Body.resolve(style);
+    let plain = egui::TextFormat::simple(font.clone(), color);
+    let strong = egui::TextFormat {
+        color: style.visuals.strong_text_color(),
+        underline: egui::Stroke::new(1.0, style.visuals.strong_text_color()),
+        ..egui::TextFormat::simple(font, color)
+    };
+    let mut job = egui::text::LayoutJob::default();
+    let mut append = |text: &str, highlight: bool| {
+        if !text.is_empty() {
+            job.append(text, 0.0, if highlight { strong.clone() } else { plain.clone() });
+        }
+    };
+    // runs of matched and unmatched characters get a section each
+    let mut start = 0;
+    let mut highlight = false;
+    for (index, (byte, _)) in text.char_indices().enumerate() {
+        if matched.contains(&index) != highlight {
+            append(&text[start..byte], highlight);
+            start = byte;
+            highlight = !highlight;
+        }
+    }
+    append(&text[start..], highlight);
+    job
+}
+
+/// typing into the listing jumps to the first item that starts with what was typed.
+/// typing the same letter again goes on to the next item starting with it
+pub struct TypeAhead {
+    typed: String,
+    typed_at: Option<Instant>
+}
+
+impl TypeAhead {
+    pub fn new() -> Self {
+        Self {
+            typed: String::new(),
+            typed_at: None
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.typed.clear();
+        self.typed_at = None;
+    }
+
+    /// whether something was typed just now, a space then belongs to the name instead of being ignored
+    pub fn is_typing(&self) -> bool {
+        self.typed_at.map(|typed_at| typed_at.elapsed() <= TYPE_AHEAD_TIMEOUT).unwrap_or(false)
+    }
+
+    /// adds the text to what was typed and returns the item it leads to, searching on from the focused one
+    pub fn type_text(&mut self, text: &str, items: &[FileListItem], focus: Option<usize>) -> Option<usize> {
+        if self.typed_at.map(|typed_at| typed_at.elapsed() > TYPE_AHEAD_TIMEOUT).unwrap_or(true) {
+            self.typed.clear();
+        }
+        self.typed_at = Some(Instant::now());
+        self.typed.push_str(text);
+
+        let typed: Vec<char> = self.typed.chars().map(lowercase).collect();
+        let first = *typed.first()?;
+        // a single letter, or the same one repeated, moves past the focused item so it can be cycled through
+        let cycling = typed.iter().all(|character| *character == first);
+        let prefix = if cycling { &typed[..1] } else { &typed[..] };
+        let start = match focus {
+            Some(focus) if cycling => focus + 1,
+            Some(focus) => focus,
+            None => 0
+        };
+        (0..items.len())
+            .map(|offset| (start + offset) % items.len())
+            .find(|index| {
+                let item = &items[*index];
+                item.visible && item.name().chars().map(lowercase).take(prefix.len()).eq(prefix.iter().copied())
+            })
+    }
+}
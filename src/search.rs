@@ -0,0 +1,296 @@
+use std::fs;
+use std::io::Read;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::ignore::{self, Gitignore};
+
+/// bigger files are skipped by the content search
+const MAX_CONTENT_SIZE: u64 = 16 * 1024 * 1024;
+/// a file with a zero byte in this many first bytes is taken as binary and skipped
+const BINARY_CHECK_BYTES: usize = 8 * 1024;
+const MAX_MATCHES_PER_FILE: usize = 100;
+const CONTEXT_LINES: usize = 1;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SearchKind {
+    Names,
+    Contents
+}
+
+impl SearchKind {
+    pub const ALL: [SearchKind; 2] = [SearchKind::Names, SearchKind::Contents];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            SearchKind::Names => "names",
+            SearchKind::Contents => "contents",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum MatchMode {
+    Substring,
+    Glob,
+    Regex
+}
+
+impl MatchMode {
+    pub const ALL: [MatchMode; 3] = [MatchMode::Substring, MatchMode::Glob, MatchMode::Regex];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            MatchMode::Substring => "contains",
+            MatchMode::Glob => "glob",
+            MatchMode::Regex => "regex",
+        }
+    }
+
+    /// a glob only makes sense for names
+    pub fn applies_to(self, kind: SearchKind) -> bool {
+        kind == SearchKind::Names || self != MatchMode::Glob
+    }
+}
+
+#[derive(Clone)]
+pub struct SearchOptions {
+    pub root: PathBuf,
+    pub kind: SearchKind,
+    pub query: String,
+    pub mode: MatchMode,
+    pub case_sensitive: bool,
+    /// comma separated globs, when there are any only the files whose name matches one of them are found
+    pub include: String,
+    /// comma separated globs of files and folders that are skipped
+    pub exclude: String,
+    pub use_gitignore: bool
+}
+
+pub struct SearchResult {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    /// the lines that matched when searching the contents
+    pub lines: Vec<LineMatch>
+}
+
+/// matching lines that are close to each other with the lines around them, the numbers start at 1
+pub struct LineMatch {
+    /// the numbers of the lines that matched
+    pub numbers: Vec<usize>,
+    pub context: Vec<(usize, String)>
+}
+
+struct Shared {
+    results: Mutex<Vec<SearchResult>>,
+    cancelled: AtomicBool,
+    running: AtomicBool
+}
+
+/// a search running on a background thread, the results are taken as they are found.
+/// dropping it cancels the search
+pub struct Search {
+    shared: Arc<Shared>
+}
+
+impl Search {
+    pub fn start(options: SearchOptions, ctx: egui::Context) -> Result<Self, String> {
+        let matcher = query_regex(&options.query, options.mode, options.case_sensitive)?;
+        let filters = Filters::new(&options)?;
+        let shared = Arc::new(Shared {
+            results: Mutex::new(Vec::new()),
+            cancelled: AtomicBool::new(false),
+            running: AtomicBool::new(true)
+        });
+        let worker_shared = shared.clone();
+        thread::spawn(move || {
+            match options.kind {
+                SearchKind::Names => search_names(&options.root, &filters, &matcher, &worker_shared, &ctx),
+                SearchKind::Contents => search_contents(&options.root, &filters, &matcher, &worker_shared, &ctx),
+            }
+            worker_shared.running.store(false, Ordering::Relaxed);
+            ctx.request_repaint();
+        });
+        Ok(Self { shared })
+    }
+
+    pub fn take_results(&self) -> Vec<SearchResult> {
+        std::mem::take(&mut *self.shared.results.lock().unwrap())
+    }
+
+    pub fn cancel(&self) {
+        self.shared.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.shared.running.load(Ordering::Relaxed)
+    }
+
+    pub fn was_cancelled(&self) -> bool {
+        self.shared.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for Search {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+fn search_names(root: &Path, filters: &Filters, matcher: &Regex, shared: &Shared, ctx: &egui::Context) {
+    walk(root, filters, &shared.cancelled, |path, is_dir| {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if matcher.is_match(&name) {
+            shared.results.lock().unwrap().push(SearchResult { path: path.to_path_buf(), is_dir, lines: Vec::new() });
+            ctx.request_repaint();
+        }
+    });
+}
+
+/// the walk hands the files to a worker per core, which read and match them
+fn search_contents(root: &Path, filters: &Filters, matcher: &Regex, shared: &Shared, ctx: &egui::Context) {
+    let workers = thread::available_parallelism().map(|count| count.get()).unwrap_or(4);
+    let (sender, receiver) = mpsc::sync_channel::<PathBuf>(256);
+    let receiver = Mutex::new(receiver);
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let Ok(path) = receiver.lock().unwrap().recv() else {
+                        break;
+                    };
+                    if shared.cancelled.load(Ordering::Relaxed) {
+                        continue;
+                    }
+                    let lines = grep_file(&path, matcher);
+                    if !lines.is_empty() {
+                        shared.results.lock().unwrap().push(SearchResult { path, is_dir: false, lines });
+                        ctx.request_repaint();
+                    }
+                }
+            });
+        }
+        walk(root, filters, &shared.cancelled, |path, is_dir| {
+            if !is_dir {
+                let _ = sender.send(path.to_path_buf());
+            }
+        });
+        drop(sender);
+    });
+}
+
+/// the matching lines of a text file, binary and huge files have none
+fn grep_file(path: &Path, matcher: &Regex) -> Vec<LineMatch> {
+    let Ok(file) = fs::File::open(path) else {
+        return Vec::new();
+    };
+    let mut bytes = Vec::new();
+    if file.take(MAX_CONTENT_SIZE + 1).read_to_end(&mut bytes).is_err() || bytes.len() as u64 > MAX_CONTENT_SIZE {
+        return Vec::new();
+    }
+    if bytes[..bytes.len().min(BINARY_CHECK_BYTES)].contains(&0) {
+        return Vec::new();
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    let lines: Vec<&str> = text.lines().collect();
+    let matching = lines.iter()
+        .enumerate()
+        .filter(|(_, line)| matcher.is_match(line))
+        .take(MAX_MATCHES_PER_FILE)
+        .map(|(index, _)| index);
+
+    // a match whose context touches the one of the match before joins it so no line is shown twice
+    let mut blocks: Vec<(Vec<usize>, Range<usize>)> = Vec::new();
+    for index in matching {
+        let context = index.saturating_sub(CONTEXT_LINES)..(index + CONTEXT_LINES + 1).min(lines.len());
+        match blocks.last_mut() {
+            Some((numbers, range)) if context.start <= range.end => {
+                numbers.push(index + 1);
+                range.end = context.end;
+            },
+            _ => blocks.push((vec![index + 1], context))
+        }
+    }
+    blocks.into_iter()
+        .map(|(numbers, range)| {
+            let context = range.map(|context| (context + 1, lines[context].to_owned())).collect();
+            LineMatch { numbers, context }
+        })
+        .collect()
+}
+
+fn query_regex(query: &str, mode: MatchMode, case_sensitive: bool) -> Result<Regex, String> {
+    let pattern = match mode {
+        MatchMode::Substring => regex::escape(query),
+        MatchMode::Glob => format!("^{}$", ignore::glob_to_regex(query)),
+        MatchMode::Regex => query.to_owned(),
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|error| error.to_string())
+}
+
+/// the include and exclude patterns and the .gitignore setting of a search
+pub struct Filters {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+    use_gitignore: bool
+}
+
+impl Filters {
+    pub fn new(options: &SearchOptions) -> Result<Self, String> {
+        Ok(Self {
+            include: ignore::name_patterns(&options.include)?,
+            exclude: ignore::name_patterns(&options.exclude)?,
+            use_gitignore: options.use_gitignore
+        })
+    }
+}
+
+/// visits every file and folder under `root` that the filters let through, symlinked folders are not followed
+pub fn walk(root: &Path, filters: &Filters, cancelled: &AtomicBool, mut visit: impl FnMut(&Path, bool)) {
+    let mut root_ignores = Vec::new();
+    if filters.use_gitignore {
+        root_ignores.extend(Gitignore::read(root));
+    }
+    let mut stack = vec![(root.to_path_buf(), root_ignores)];
+    while let Some((directory, ignores)) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&directory) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if cancelled.load(Ordering::Relaxed) {
+                return;
+            }
+            let path = entry.path();
+            let is_dir = entry.file_type().map(|file_type| file_type.is_dir()).unwrap_or(false);
+            let name = entry.file_name().to_string_lossy().to_string();
+            if filters.exclude.iter().any(|pattern| pattern.is_match(&name)) {
+                continue;
+            }
+            if filters.use_gitignore && (name == ".git" || ignore::is_ignored(&ignores, &path, is_dir)) {
+                continue;
+            }
+            if is_dir {
+                let mut ignores = ignores.clone();
+                if filters.use_gitignore {
+                    ignores.extend(Gitignore::read(&path));
+                }
+                // folders are still walked into when there are include patterns, they just aren't results
+                if filters.include.is_empty() {
+                    visit(&path, true);
+                }
+                stack.push((path, ignores));
+            }
+            else if filters.include.is_empty() || filters.include.iter().any(|pattern| pattern.is_match(&name)) {
+                visit(&path, false);
+            }
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::path::{Path, PathBuf};
+
+use crate::search::{MatchMode, Search, SearchKind, SearchOptions, SearchResult};
+
+pub enum SearchAction {
+    Open(PathBuf),
+    /// opens the folder the result is in with the result selected
+    Reveal(PathBuf)
+}
+
+/// a row of the results list, content results take a row for every line shown under them
+enum Row {
+    Result(usize),
+    /// the result, the match in it and the line of the match context
+    Line(usize, usize, usize)
+}
+
+pub struct SearchView {
+    options: SearchOptions,
+    search: Option<Search>,
+    results: Vec<SearchResult>,
+    rows: Vec<Row>,
+    selected: Option<usize>,
+    error: Option<String>
+}
+
+impl SearchView {
+    pub fn new() -> Self {
+        Self {
+            options: SearchOptions {
+                root: PathBuf::new(),
+                kind: SearchKind::Names,
+                query: String::new(),
+                mode: MatchMode::Substring,
+                case_sensitive: false,
+                include: String::new(),
+                exclude: String::new(),
+                use_gitignore: true
+            },
+            search: None,
+            results: Vec::new(),
+            rows: Vec::new(),
+            selected: None,
+            error: None
+        }
+    }
+
+    /// starts searching `root` again with the current options, the previous search is cancelled
+    fn start(&mut self, root: &Path, ctx: &egui::Context) {
+        self.options.root = root.to_path_buf();
+        self.results.clear();
+        self.rows.clear();
+        self.selected = None;
+        self.search = None;
+        self.error = None;
+        if self.options.query.is_empty() {
+            return;
+        }
+        match Search::start(self.options.clone(), ctx.clone()) {
+            Ok(search) => self.search = Some(search),
+            Err(error) => self.error = Some(error)
+        }
+    }
+
+    /// returns false when the window was closed
+    pub fn show(&mut self, ctx: &egui::Context, root: &Path, action: &mut Option<SearchAction>) -> bool {
+        if let Some(search) = &self.search {
+            for result in search.take_results() {
+                let index = self.results.len();
+                self.rows.push(Row::Result(index));
+                for (match_index, line) in result.lines.iter().enumerate() {
+                    self.rows.extend((0..line.context.len()).map(|line_index| Row::Line(index, match_index, line_index)));
+                }
+                self.results.push(result);
+            }
+        }
+        let running = self.search.as_ref().map(Search::is_running).unwrap_or(false);
+
+        let mut open = true;
+        egui::Window::new("Search")
+            .open(&mut open)
+            .default_size(egui::vec2(500.0, 400.0))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let hint = match self.options.kind {
+                        SearchKind::Names => "name",
+                        SearchKind::Contents => "text",
+                    };
+                    let query = ui.add(egui::TextEdit::singleline(&mut self.options.query).hint_text(hint));
+                    let submitted = query.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+                    if running {
+                        if ui.button("cancel").clicked() {
+                            if let Some(search) = &self.search {
+                                search.cancel();
+                            }
+                        }
+                    }
+                    else if ui.button("search").clicked() || submitted {
+                        self.start(root, ctx);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source("search_kind")
+                        .selected_text(self.options.kind.title())
+                        .show_ui(ui, |ui| {
+                            for kind in SearchKind::ALL {
+                                ui.selectable_value(&mut self.options.kind, kind, kind.title());
+                            }
+                        });
+                    if !self.options.mode.applies_to(self.options.kind) {
+                        self.options.mode = MatchMode::Substring;
+                    }
+                    egui::ComboBox::from_id_source("search_mode")
+                        .selected_text(self.options.mode.title())
+                        .show_ui(ui, |ui| {
+                            for mode in MatchMode::ALL.into_iter().filter(|mode| mode.applies_to(self.options.kind)) {
+                                ui.selectable_value(&mut self.options.mode, mode, mode.title());
+                            }
+                        });
+                    ui.checkbox(&mut self.options.case_sensitive, "match case");
+                    ui.checkbox(&mut self.options.use_gitignore, "use .gitignore");
+                });
+                egui::Grid::new("search_patterns").num_columns(2).show(ui, |ui| {
+                    ui.label("include");
+                    ui.add(egui::TextEdit::singleline(&mut self.options.include).hint_text("*.rs, *.toml"));
+                    ui.end_row();
+                    ui.label("exclude");
+                    ui.add(egui::TextEdit::singleline(&mut self.options.exclude).hint_text("target, node_modules"));
+                    ui.end_row();
+                });
+                ui.separator();
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                    return;
+                }
+                if self.search.is_none() {
+                    ui.weak(format!("Searches {}", root.display()));
+                    return;
+                }
+                ui.horizontal(|ui| {
+                    if running {
+                        ui.spinner();
+                    }
+                    let cancelled = self.search.as_ref().map(Search::was_cancelled).unwrap_or(false);
+                    let status = if cancelled { "cancelled, " } else { "" };
+                    ui.weak(format!("{status}{} results in {}", self.results.len(), self.options.root.display()));
+                });
+                self.results_list(ui, action);
+            });
+        open
+    }
+
+    fn results_list(&mut self, ui: &mut egui::Ui, action: &mut Option<SearchAction>) {
+        let row_height = ui.spacing().interact_size.y;
+        egui::ScrollArea::both().auto_shrink([false, false]).show_rows(ui, row_height, self.rows.len(), |ui, row_range| {
+            for row in row_range {
+                let (index, text) = match self.rows[row] {
+                    Row::Result(index) => {
+                        let result = &self.results[index];
+                        let relative = result.path.strip_prefix(&self.options.root).unwrap_or(&result.path);
+                        let mut text = relative.to_string_lossy().to_string();
+                        if result.is_dir {
+                            text.push('/');
+                        }
+                        (index, egui::RichText::new(text))
+                    },
+                    Row::Line(index, match_index, line_index) => {
+                        let line = &self.results[index].lines[match_index];
+                        let (number, text) = &line.context[line_index];
+                        let text = egui::RichText::new(format!("{number:>6}: {text}")).monospace();
+                        (index, if line.numbers.contains(number) { text } else { text.weak() })
+                    }
+                };
+                let path = &self.results[index].path;
+                let response = ui.add(egui::SelectableLabel::new(self.selected == Some(row), text));
+                if response.clicked() {
+                    self.selected = Some(row);
+                }
+                if response.double_clicked() {
+                    *action = Some(SearchAction::Open(path.clone()));
+                }
+                response.context_menu(|ui| {
+                    if ui.button("open").clicked() {
+                        *action = Some(SearchAction::Open(path.clone()));
+                        ui.close_menu();
+                    }
+                    if ui.button("open containing folder").clicked() {
+                        *action = Some(SearchAction::Reveal(path.clone()));
+                        ui.close_menu();
+                    }
+                });
+            }
+        });
+    }
+}
@@ -0,0 +1,135 @@
+use std::ops::RangeInclusive;
+
+use crate::file_list::FileListItem;
+
+/// the selection state of a listing, the selected flags themselves live on the items.
+/// the anchor is where a shift range starts and the focus is the item that was interacted with last
+pub struct Selection {
+    pub anchor: Option<usize>,
+    pub focus: Option<usize>
+}
+
+impl Selection {
+    pub fn new() -> Self {
+        Self {
+            anchor: None,
+            focus: None
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.anchor = None;
+        self.focus = None;
+    }
+
+    /// ctrl toggles the item, shift selects the range from the anchor and both together add the range
+    pub fn click(&mut self, items: &mut [FileListItem], index: usize, modifiers: egui::Modifiers) {
+        if index >= items.len() {
+            return;
+        }
+        if modifiers.shift {
+            let anchor = self.anchor.filter(|anchor| *anchor < items.len()).unwrap_or(index);
+            if !modifiers.command {
+                clear(items);
+            }
+            select_range(items, anchor.min(index)..=anchor.max(index));
+            self.anchor = Some(anchor);
+        }
+        else if modifiers.command {
+            items[index].selected = !items[index].selected;
+            self.anchor = Some(index);
+        }
+        else {
+            clear(items);
+            items[index].selected = true;
+            self.anchor = Some(index);
+        }
+        self.focus = Some(index);
+    }
+
+    /// a right click keeps the selection when it was on a selected item so the context menu acts on all of them
+    pub fn context_click(&mut self, items: &mut [FileListItem], index: usize) {
+        if items.get(index).map(|item| !item.selected).unwrap_or(false) {
+            self.click(items, index, egui::Modifiers::NONE);
+        }
+        else {
+            self.focus = Some(index);
+        }
+    }
+
+    pub fn select_all(&mut self, items: &mut [FileListItem]) {
+        for item in items.iter_mut().filter(|item| item.visible) {
+            item.selected = true;
+        }
+    }
+
+    pub fn invert(&mut self, items: &mut [FileListItem]) {
+        for item in items.iter_mut().filter(|item| item.visible) {
+            item.selected = !item.selected;
+        }
+    }
+
+    pub fn clear(&mut self, items: &mut [FileListItem]) {
+        clear(items);
+        self.anchor = None;
+    }
+
+    /// the rubber band selection, `additive` keeps the items that were selected before
+    pub fn select_many(&mut self, items: &mut [FileListItem], indices: &[usize], additive: bool) {
+        if !additive {
+            clear(items);
+        }
+        for index in indices {
+            if let Some(item) = items.get_mut(*index) {
+                item.selected = true;
+            }
+        }
+    }
+
+    /// moves the focus by `delta` visible items like an arrow key does, `extend` selects the range like a shift click
+    pub fn step(&mut self, items: &mut [FileListItem], delta: isize, extend: bool) -> Option<usize> {
+        let index = self.stepped(items, delta)?;
+        let modifiers = if extend { egui::Modifiers::SHIFT } else { egui::Modifiers::NONE };
+        self.click(items, index, modifiers);
+        Some(index)
+    }
+
+    /// moves only the focus, like ctrl and an arrow key, the selection stays as it is
+    pub fn move_focus(&mut self, items: &[FileListItem], delta: isize) -> Option<usize> {
+        let index = self.stepped(items, delta)?;
+        self.focus = Some(index);
+        Some(index)
+    }
+
+    /// selects or deselects the focused item, like ctrl and space
+    pub fn toggle_focus(&mut self, items: &mut [FileListItem]) {
+        if let Some(focus) = self.focus.filter(|focus| items.get(*focus).map(|item| item.visible).unwrap_or(false)) {
+            self.click(items, focus, egui::Modifiers::COMMAND);
+        }
+    }
+
+    /// the visible item `delta` visible items away from the focus, the first one when nothing is focused
+    fn stepped(&self, items: &[FileListItem], delta: isize) -> Option<usize> {
+        let visible: Vec<usize> = (0..items.len()).filter(|index| items[*index].visible).collect();
+        if visible.is_empty() {
+            return None;
+        }
+        let position = match self.focus.and_then(|focus| visible.iter().position(|index| *index == focus)) {
+            Some(position) => (position as isize).saturating_add(delta).clamp(0, visible.len() as isize - 1) as usize,
+            None => 0
+        };
+        Some(visible[position])
+    }
+}
+
+fn clear(items: &mut [FileListItem]) {
+    for item in items.iter_mut() {
+        item.selected = false;
+    }
+}
+
+fn select_range(items: &mut [FileListItem], range: RangeInclusive<usize>) {
+    for item in items[range].iter_mut().filter(|item| item.visible) {
+        item.selected = true;
+    }
+}
@@ -0,0 +1,210 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::details::{Column, SortOrder, Sorting};
+use crate::file_list::{IconSize, ViewMode};
+use crate::tab::Tab;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    System,
+    Dark,
+    Light
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::System, Theme::Dark, Theme::Light];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Theme::System => "system",
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+        }
+    }
+
+    /// whether the theme is dark, the system theme is dark when it isn't known
+    pub fn is_dark(self, system: Option<eframe::Theme>) -> bool {
+        match self {
+            Theme::System => system != Some(eframe::Theme::Light),
+            Theme::Dark => true,
+            Theme::Light => false,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaneSession {
+    pub tabs: Vec<PathBuf>,
+    pub active_tab: usize
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub dual_pane: bool,
+    pub active_pane: usize,
+    pub panes: Vec<PaneSession>
+}
+
+/// missing keys take their default so the files of older versions load as they are
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub theme: Theme,
+    /// the view of new tabs, it is taken from the active tab when the settings are saved
+    pub view_mode: ViewMode,
+    pub icon_size: IconSize,
+    pub sort_column: Column,
+    pub sort_order: SortOrder,
+    pub folders_first: bool,
+    pub show_hidden: bool,
+    pub ignore_patterns: String,
+    pub use_gitignore: bool,
+    pub show_sidebar: bool,
+    pub show_preview: bool,
+    /// whether typing into a listing opens the quick filter instead of jumping to the item
+    pub typing_filters: bool,
+    /// whether the tabs of the last session are opened again on start
+    pub restore_session: bool,
+    /// whether explorers started later open their paths in this one
+    pub single_instance: bool,
+    /// whether "show in folder" of other programs opens this explorer over D-Bus
+    pub file_manager_service: bool,
+    pub window_size: Option<[f32; 2]>,
+    pub window_position: Option<[f32; 2]>,
+    pub window_maximized: bool,
+    /// the tabs that were open when the explorer was closed last
+    pub session: Session
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: Theme::System,
+            view_mode: ViewMode::List,
+            icon_size: IconSize::Medium,
+            sort_column: Column::Name,
+            sort_order: SortOrder::Ascending,
+            folders_first: true,
+            show_hidden: false,
+            ignore_patterns: String::new(),
+            use_gitignore: false,
+            show_sidebar: true,
+            show_preview: false,
+            typing_filters: false,
+            restore_session: true,
+            single_instance: false,
+            file_manager_service: false,
+            window_size: None,
+            window_position: None,
+            window_maximized: false,
+            session: Session::default()
+        }
+    }
+}
+
+impl Settings {
+    /// the settings are always usable, the message tells what went wrong reading them
+    pub fn load() -> (Self, Option<String>) {
+        let Some(file) = settings_file() else {
+            return (Self::default(), None);
+        };
+        let content = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return (Self::default(), None),
+            Err(error) => return (Self::default(), Some(format!("Couldn't read the settings: {error}")))
+        };
+        match toml::from_str::<toml::Table>(&content) {
+            Ok(table) => {
+                let (settings, skipped) = Self::from_table(table);
+                let message = (!skipped.is_empty()).then(|| format!("Some settings couldn't be read and were reset: {}", skipped.join(", ")));
+                (settings, message)
+            },
+            Err(error) => {
+                // the file would be overwritten on exit, so it is kept for whoever wants to fix it
+                let backup = file.with_extension("toml.bak");
+                let message = match fs::rename(&file, &backup) {
+                    Ok(()) => format!("The settings couldn't be read and were reset, the old file was kept as {}:\n{error}", backup.display()),
+                    Err(_) => format!("The settings couldn't be read and were reset:\n{error}")
+                };
+                (Self::default(), Some(message))
+            }
+        }
+    }
+
+    /// takes the keys one at a time, returns the ones that were left at their defaults
+    fn from_table(table: toml::Table) -> (Self, Vec<String>) {
+        let mut settings = Self::default();
+        let mut skipped = Vec::new();
+        for (key, value) in table {
+            let Ok(toml::Value::Table(mut merged)) = toml::Value::try_from(&settings) else {
+                break;
+            };
+            merged.insert(key.clone(), value);
+            match toml::Value::Table(merged).try_into() {
+                Ok(merged) => settings = merged,
+                Err(_) => skipped.push(key)
+            }
+        }
+        (settings, skipped)
+    }
+
+    /// writes a new file and moves it over the old one so a crash can't leave half a file behind
+    pub fn save(&self) -> Result<(), String> {
+        let Some(file) = settings_file() else {
+            return Ok(());
+        };
+        let content = toml::to_string_pretty(self).map_err(|error| format!("Couldn't save the settings: {error}"))?;
+        let temporary = file.with_extension("toml.tmp");
+        let result = file.parent().map(fs::create_dir_all).unwrap_or(Ok(()))
+            .and_then(|_| fs::write(&temporary, content))
+            .and_then(|_| fs::rename(&temporary, &file));
+        result.map_err(|error| format!("Couldn't save the settings: {error}"))
+    }
+
+    pub fn sorting(&self) -> Sorting {
+        Sorting {
+            column: self.sort_column,
+            order: self.sort_order,
+            folders_first: self.folders_first
+        }
+    }
+
+    /// shows the tab the way new tabs are shown
+    pub fn apply_view(&self, tab: &mut Tab) {
+        tab.file_list.view_mode = self.view_mode;
+        tab.file_list.icon_size = self.icon_size;
+        tab.file_list.set_sorting(self.sorting());
+        tab.sort_childs();
+    }
+
+    /// new tabs are shown like the tab
+    pub fn take_view(&mut self, tab: &Tab) {
+        let sorting = tab.file_list.sorting();
+        self.view_mode = tab.file_list.view_mode;
+        self.icon_size = tab.file_list.icon_size;
+        self.sort_column = sorting.column;
+        self.sort_order = sorting.order;
+        self.folders_first = sorting.folders_first;
+    }
+}
+
+/// `$XDG_CONFIG_HOME/file-explorer`, where the settings and the bookmarks are kept
+pub fn config_dir() -> Option<PathBuf> {
+    let config = match env::var_os("XDG_CONFIG_HOME") {
+        Some(config) if !config.is_empty() => PathBuf::from(config),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config")
+    };
+    Some(config.join("file-explorer"))
+}
+
+fn settings_file() -> Option<PathBuf> {
+    Some(config_dir()?.join("settings.toml"))
+}
@@ -0,0 +1,279 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::file_list::{self, FileListItem};
+use crate::history::History;
+use crate::ignore::{Gitignore, IgnoreSettings};
+use crate::quick_filter;
+use crate::selection::Selection;
+use crate::watcher::{self, Watcher};
+
+pub struct Tab {
+    pub id: egui::Id,
+    pub directory: PathBuf,
+    pub child_directories: Vec<FileListItem>,
+    pub file_list: file_list::FileListWidget,
+    pub selection: Selection,
+    pub history: History,
+    /// the quick filter query, the bar is shown while this is set
+    pub filter: Option<String>,
+    /// the text of the location bar while a path is typed into it
+    pub location: Option<String>,
+    pub type_ahead: quick_filter::TypeAhead,
+    ignore: IgnoreSettings,
+    /// the .gitignore files that apply to the directory, read again when it is refreshed
+    gitignores: Vec<Gitignore>,
+    watcher: Box<dyn Watcher>
+}
+
+impl Tab {
+    pub fn new(path: PathBuf, ignore: IgnoreSettings, ctx: &egui::Context) -> Result<Self, String> {
+        if !path.is_dir() {
+            return Err(format!("{} is not a directory", path.display()));
+        }
+        let context = ctx.clone();
+        let watcher = Box::new(watcher::FileSystemWatcher::new(path.clone(), move || {
+            context.request_repaint();
+        }));
+
+        let mut tab = Self {
+            id: next_tab_id(ctx),
+            directory: path,
+            child_directories: Vec::new(),
+            file_list: file_list::FileListWidget::new(),
+            selection: Selection::new(),
+            history: History::new(),
+            filter: None,
+            location: None,
+            type_ahead: quick_filter::TypeAhead::new(),
+            ignore,
+            gitignores: Vec::new(),
+            watcher
+        };
+        tab.refresh_childs();
+        Ok(tab)
+    }
+
+    pub fn title(&self) -> String {
+        match self.directory.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => self.directory.to_string_lossy().to_string()
+        }
+    }
+
+    pub fn refresh_childs(&mut self) {
+        self.child_directories.clear();
+        self.selection.reset();
+        self.gitignores = if self.ignore.use_gitignore { Gitignore::for_directory(&self.directory) } else { Vec::new() };
+        if let Ok(entries) = fs::read_dir(&self.directory) {
+            for entry in entries {
+                if let Ok(entry) = entry {
+                    self.add_child(FileListItem::new(entry.path()));
+                }
+            }
+        }
+        self.sort_childs();
+    }
+
+    /// adds the item to the listing unless the ignore settings leave it out
+    fn add_child(&mut self, item: FileListItem) {
+        if !self.ignore.hides(&item.path, item.is_dir(), &self.gitignores) {
+            self.child_directories.push(item);
+        }
+    }
+
+    /// lists the directory again with other ignore settings, the selected items stay selected
+    pub fn set_ignore(&mut self, ignore: IgnoreSettings) {
+        let selected: Vec<PathBuf> = self.child_directories.iter().filter(|item| item.selected).map(|item| item.path.clone()).collect();
+        self.ignore = ignore;
+        self.refresh_childs();
+        for item in &mut self.child_directories {
+            item.selected = item.visible && selected.contains(&item.path);
+        }
+    }
+
+    /// sorts the listing the way the file list is set to, the anchor and the focus move with their items.
+    /// new items go through here too so they are filtered as well
+    pub fn sort_childs(&mut self) {
+        let paths = self.selection_paths();
+        self.file_list.sort_items(&mut self.child_directories);
+        self.apply_filter();
+        self.restore_selection(paths);
+    }
+
+    /// the items of the anchor and the focus, to find them again once the indices changed
+    fn selection_paths(&self) -> [Option<PathBuf>; 2] {
+        [self.selection.anchor, self.selection.focus]
+            .map(|index| index.and_then(|index| self.child_directories.get(index)).map(|item| item.path.clone()))
+    }
+
+    /// points the anchor and the focus at their items again, they are dropped when the item is gone
+    fn restore_selection(&mut self, [anchor, focus]: [Option<PathBuf>; 2]) {
+        let find = |path: Option<PathBuf>| path.and_then(|path| self.child_directories.iter().position(|item| item.path == path));
+        self.selection.anchor = find(anchor);
+        self.selection.focus = find(focus);
+    }
+
+    /// filters the listing by the query, None shows all the items again and scrolls back to the focused one
+    pub fn set_filter(&mut self, filter: Option<String>) {
+        let closed = self.filter.is_some() && filter.is_none();
+        self.filter = filter;
+        self.apply_filter();
+        if let Some(focus) = self.selection.focus.filter(|_| closed) {
+            self.file_list.scroll_to(focus);
+        }
+    }
+
+    /// hides the items the filter doesn't match, they are deselected so nothing acts on items that can't be seen
+    fn apply_filter(&mut self) {
+        for item in &mut self.child_directories {
+            let matched = match &self.filter {
+                Some(query) => quick_filter::match_chars(&item.name(), query),
+                None => Some(Vec::new())
+            };
+            item.visible = matched.is_some();
+            item.matched = matched.unwrap_or_default();
+            if !item.visible {
+                item.selected = false;
+            }
+        }
+    }
+
+    pub fn change_dir(&mut self, path: PathBuf) -> Result<(), String> {
+        if path == self.directory {
+            return Ok(());
+        }
+        let previous = self.directory.clone();
+        self.set_directory(path)?;
+        self.history.push(previous);
+        Ok(())
+    }
+
+    fn set_directory(&mut self, path: PathBuf) -> Result<(), String> {
+        self.watcher.watch(path.clone())?;
+        self.directory = path;
+        self.filter = None;
+        self.type_ahead.reset();
+        self.refresh_childs();
+        Ok(())
+    }
+
+    pub fn go_back(&mut self, steps: usize) -> Result<(), String> {
+        if let Some(path) = self.history.peek_back(steps).cloned() {
+            let current = self.directory.clone();
+            self.set_directory(path)?;
+            self.history.go_back(current, steps);
+        }
+        Ok(())
+    }
+
+    pub fn go_forward(&mut self, steps: usize) -> Result<(), String> {
+        if let Some(path) = self.history.peek_forward(steps).cloned() {
+            let current = self.directory.clone();
+            self.set_directory(path)?;
+            self.history.go_forward(current, steps);
+        }
+        Ok(())
+    }
+
+    pub fn go_up(&mut self) -> Result<(), String> {
+        if let Some(parent) = self.directory.parent() {
+            self.change_dir(parent.to_path_buf())?;
+        }
+        Ok(())
+    }
+
+    /// opens the folder the path is in and selects it
+    pub fn reveal(&mut self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            self.change_dir(parent.to_path_buf())?;
+        }
+        if let Some(index) = self.child_directories.iter().position(|item| item.path == path) {
+            self.selection.click(&mut self.child_directories, index, egui::Modifiers::NONE);
+            self.file_list.scroll_to(index);
+        }
+        Ok(())
+    }
+
+    /// shows the files of one folder selected together, the first of them is scrolled to
+    pub fn reveal_all(&mut self, paths: &[PathBuf]) -> Result<(), String> {
+        let Some(first) = paths.first() else {
+            return Ok(());
+        };
+        self.reveal(first)?;
+        for path in &paths[1..] {
+            if let Some(index) = self.child_directories.iter().position(|item| item.path == *path && !item.selected) {
+                self.selection.click(&mut self.child_directories, index, egui::Modifiers::COMMAND);
+            }
+        }
+        Ok(())
+    }
+
+    /// applies the changes reported by the watcher to the listing, returns true when a folder in it
+    /// may have been created, removed or renamed
+    pub fn update_from_watcher(&mut self) -> bool {
+        let mut folders_changed = false;
+        while let Some(change) = self.watcher.look_for_changes() {
+            if self.ignore.use_gitignore && changes_gitignore(&change) {
+                self.refresh_childs();
+                folders_changed = true;
+                continue;
+            }
+            match change {
+                watcher::Change::Unknown => {
+                    self.refresh_childs();
+                    folders_changed = true;
+                },
+                watcher::Change::Create(kind, path) => {
+                    folders_changed |= matches!(kind, watcher::CreateKind::Directory);
+                    self.add_child(FileListItem::new(path));
+                    self.sort_childs();
+                },
+                // the removed path can't be asked whether it was a folder anymore
+                watcher::Change::Remove(path) => {
+                    let paths = self.selection_paths();
+                    self.child_directories.retain(|p| *p.path != path);
+                    self.restore_selection(paths);
+                    folders_changed = true;
+                },
+                // the new name can be left out when the old one wasn't, or the other way around.
+                // the anchor and the focus follow the renamed item
+                watcher::Change::Rename(from, to) => {
+                    folders_changed |= to.is_dir();
+                    let paths = self.selection_paths().map(|path| path.map(|path| if path == from { to.clone() } else { path }));
+                    self.child_directories.retain(|p| *p.path != from);
+                    self.add_child(FileListItem::new(to));
+                    self.sort_childs();
+                    self.restore_selection(paths);
+                },
+                watcher::Change::Modify(path) => {
+                    let item = self.child_directories.iter_mut().find(|p| *p.path == path);
+                    if let Some(item) = item {
+                        item.refresh_metadata();
+                    }
+                },
+            }
+        }
+        folders_changed
+    }
+}
+
+/// whether a .gitignore was created, removed, renamed or changed, the listing has to be read again then
+fn changes_gitignore(change: &watcher::Change) -> bool {
+    let is_gitignore = |path: &PathBuf| path.ends_with(".gitignore");
+    match change {
+        watcher::Change::Unknown => false,
+        watcher::Change::Create(_, path) | watcher::Change::Remove(path) | watcher::Change::Modify(path) => is_gitignore(path),
+        watcher::Change::Rename(from, to) => is_gitignore(from) || is_gitignore(to)
+    }
+}
+
+fn next_tab_id(ctx: &egui::Context) -> egui::Id {
+    let counter_id = egui::Id::new("tab_id_counter");
+    let count = ctx.data_mut(|d| {
+        let count = d.get_temp_mut_or_default::<u64>(counter_id);
+        *count += 1;
+        *count
+    });
+    egui::Id::new("tab").with(count)
+}
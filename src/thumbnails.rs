@@ -0,0 +1,233 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::io::{self, BufWriter};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use md5::{Digest, Md5};
+
+const WORKERS: usize = 4;
+/// the loaded thumbnails are dropped all at once past this, they come back quickly from the disk cache
+const MAX_LOADED: usize = 512;
+const EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "gif", "webp", "bmp"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThumbnailSize {
+    Normal,
+    Large
+}
+
+impl ThumbnailSize {
+    /// the smallest size that doesn't have to be scaled up to be shown at `pixels`
+    pub fn for_pixels(pixels: f32) -> Self {
+        if pixels <= ThumbnailSize::Normal.pixels() as f32 {
+            ThumbnailSize::Normal
+        }
+        else {
+            ThumbnailSize::Large
+        }
+    }
+
+    fn pixels(self) -> u32 {
+        match self {
+            ThumbnailSize::Normal => 128,
+            ThumbnailSize::Large => 256,
+        }
+    }
+
+    fn directory_name(self) -> &'static str {
+        match self {
+            ThumbnailSize::Normal => "normal",
+            ThumbnailSize::Large => "large",
+        }
+    }
+}
+
+pub fn is_supported(path: &Path) -> bool {
+    match path.extension() {
+        Some(extension) => EXTENSIONS.iter().any(|supported| extension.eq_ignore_ascii_case(supported)),
+        None => false
+    }
+}
+
+type Key = (PathBuf, SystemTime, ThumbnailSize);
+
+struct Shared {
+    /// the requests waiting for a worker, the most recent one is taken first since it is most likely still on screen
+    requests: Mutex<Vec<Key>>,
+    available: Condvar,
+    results: Mutex<Vec<(Key, Option<egui::ColorImage>)>>
+}
+
+/// loads thumbnails from the cache and generates the missing ones on a few background threads.
+/// the cache is the one of the freedesktop thumbnail standard so it is shared with other file managers
+pub struct Thumbnailer {
+    shared: Arc<Shared>,
+    pending: HashSet<Key>,
+    /// None when the thumbnail can't be made, so it isn't requested again
+    loaded: HashMap<Key, Option<egui_extras::RetainedImage>>
+}
+
+impl Thumbnailer {
+    pub fn new(ctx: egui::Context) -> Self {
+        let shared = Arc::new(Shared {
+            requests: Mutex::new(Vec::new()),
+            available: Condvar::new(),
+            results: Mutex::new(Vec::new())
+        });
+        for _ in 0..WORKERS {
+            let shared = shared.clone();
+            let ctx = ctx.clone();
+            thread::spawn(move || worker(&shared, &ctx));
+        }
+        Self {
+            shared,
+            pending: HashSet::new(),
+            loaded: HashMap::new()
+        }
+    }
+
+    /// the thumbnail of an image modified at `modified`, None until it is ready or when there can't be one
+    pub fn get(&mut self, path: &Path, modified: SystemTime, size: ThumbnailSize) -> Option<&egui_extras::RetainedImage> {
+        self.receive();
+        let key = (path.to_path_buf(), modified, size);
+        if !self.loaded.contains_key(&key) && self.pending.insert(key.clone()) {
+            self.shared.requests.lock().unwrap().push(key.clone());
+            self.shared.available.notify_one();
+        }
+        self.loaded.get(&key)?.as_ref()
+    }
+
+    fn receive(&mut self) {
+        let results = std::mem::take(&mut *self.shared.results.lock().unwrap());
+        if results.is_empty() {
+            return;
+        }
+        if self.loaded.len() + results.len() > MAX_LOADED {
+            self.loaded.clear();
+        }
+        for (key, image) in results {
+            self.pending.remove(&key);
+            let image = image.map(|image| egui_extras::RetainedImage::from_color_image(key.0.to_string_lossy(), image));
+            self.loaded.insert(key, image);
+        }
+    }
+}
+
+fn worker(shared: &Shared, ctx: &egui::Context) {
+    loop {
+        let key = {
+            let mut requests = shared.requests.lock().unwrap();
+            loop {
+                match requests.pop() {
+                    Some(key) => break key,
+                    None => requests = shared.available.wait(requests).unwrap()
+                }
+            }
+        };
+        let image = thumbnail(&key.0, key.1, key.2).ok().map(|image| {
+            let size = [image.width() as usize, image.height() as usize];
+            egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw())
+        });
+        shared.results.lock().unwrap().push((key, image));
+        ctx.request_repaint();
+    }
+}
+
+/// reads the thumbnail from the cache when it is still up to date, otherwise makes it and saves it there
+fn thumbnail(path: &Path, modified: SystemTime, size: ThumbnailSize) -> io::Result<image::RgbaImage> {
+    let path = path.canonicalize()?;
+    let modified = modified.duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0).to_string();
+    let uri = file_uri(&path);
+    let cache_dir = cache_dir().ok_or_else(|| io::Error::other("there is no cache directory"))?;
+    // thumbnails of the thumbnails themselves are never made
+    if path.starts_with(&cache_dir) {
+        return Err(io::Error::other("the file is a thumbnail"));
+    }
+    let cache_path = cache_dir.join(size.directory_name()).join(format!("{}.png", md5_hex(uri.as_bytes())));
+    if let Some(image) = read_cached(&cache_path, &modified) {
+        return Ok(image);
+    }
+
+    let image = image::open(&path).map_err(io::Error::other)?;
+    let pixels = size.pixels();
+    // images smaller than the thumbnail are kept as they are instead of being scaled up
+    let image = if image.width() > pixels || image.height() > pixels {
+        image.thumbnail(pixels, pixels)
+    }
+    else {
+        image
+    };
+    let image = image.to_rgba8();
+    // the cache is only an optimization, the thumbnail is still shown when it can't be written
+    let _ = write_cached(&cache_path, &image, &uri, &modified);
+    Ok(image)
+}
+
+fn read_cached(cache_path: &Path, modified: &str) -> Option<image::RgbaImage> {
+    let bytes = fs::read(cache_path).ok()?;
+    let reader = png::Decoder::new(bytes.as_slice()).read_info().ok()?;
+    let up_to_date = reader.info().uncompressed_latin1_text.iter()
+        .any(|chunk| chunk.keyword == "Thumb::MTime" && chunk.text == modified);
+    if !up_to_date {
+        return None;
+    }
+    let image = image::load_from_memory_with_format(&bytes, image::ImageFormat::Png).ok()?;
+    Some(image.to_rgba8())
+}
+
+fn write_cached(cache_path: &Path, image: &image::RgbaImage, uri: &str, modified: &str) -> io::Result<()> {
+    if let Some(parent) = cache_path.parent() {
+        fs::DirBuilder::new().recursive(true).mode(0o700).create(parent)?;
+    }
+    // written next to its place and renamed so other programs never read half of it
+    let temp_path = cache_path.with_extension(format!("{}.tmp", std::process::id()));
+    let file = fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(&temp_path)?;
+    let result = (|| {
+        let mut encoder = png::Encoder::new(BufWriter::new(file), image.width(), image.height());
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.add_text_chunk(String::from("Thumb::URI"), uri.to_owned())?;
+        encoder.add_text_chunk(String::from("Thumb::MTime"), modified.to_owned())?;
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(image.as_raw())?;
+        writer.finish()?;
+        fs::rename(&temp_path, cache_path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    let cache = match env::var_os("XDG_CACHE_HOME") {
+        Some(cache) if !cache.is_empty() => PathBuf::from(cache),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".cache")
+    };
+    Some(cache.join("thumbnails"))
+}
+
+/// the uri is escaped the same way glib does it, the cache file name is its hash so it has to match exactly
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for &byte in path.as_os_str().as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=:@/".contains(&byte) {
+            uri.push(byte as char);
+        }
+        else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+/// the md5 digest as lowercase hex, the thumbnail standard names the cache files with it
+fn md5_hex(data: &[u8]) -> String {
+    Md5::digest(data).iter().map(|byte| format!("{byte:02x}")).collect()
+}
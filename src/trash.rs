@@ -0,0 +1,342 @@
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+use std::path::{Path, PathBuf};
+
+const INFO_EXTENSION: &str = "trashinfo";
+const STICKY_BIT: u32 = 0o1000;
+
+#[derive(Clone)]
+pub struct TrashItem {
+    /// the trash directory holding the `files` and `info` directories
+    pub trash_dir: PathBuf,
+    /// the name inside of the `files` directory
+    pub name: String,
+    pub original_path: PathBuf,
+    pub deletion_date: String
+}
+
+impl TrashItem {
+    pub fn file_path(&self) -> PathBuf {
+        self.trash_dir.join("files").join(&self.name)
+    }
+
+    pub fn info_path(&self) -> PathBuf {
+        self.trash_dir.join("info").join(format!("{}.{INFO_EXTENSION}", self.name))
+    }
+}
+
+/// trashes the path the way the freedesktop.org trash specification describes
+pub fn move_to_trash(path: &Path) -> io::Result<TrashItem> {
+    // only the parent is resolved, a symlink is trashed itself and not its target
+    let name = path.file_name().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("{} cannot be trashed", path.display())))?;
+    let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let path = parent.canonicalize()?.join(name);
+    let (trash_dir, top_dir) = trash_dir_for(&path)?;
+    let files_dir = trash_dir.join("files");
+    let info_dir = trash_dir.join("info");
+    create_private_dir(&files_dir)?;
+    create_private_dir(&info_dir)?;
+
+    // the original path is relative to the top directory for trashes that are not in the home directory
+    let stored_path = match &top_dir {
+        Some(top_dir) => path.strip_prefix(top_dir).unwrap_or(&path),
+        None => &path
+    };
+    let deletion_date = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S");
+    let info = format!("[Trash Info]\nPath={}\nDeletionDate={deletion_date}\n", encode_path(stored_path));
+
+    // the info file is created first and atomically, it reserves the name in the trash
+    for attempt in 1.. {
+        let trashed_name = numbered_name(Path::new(name), attempt);
+        let info_path = info_dir.join(format!("{trashed_name}.{INFO_EXTENSION}"));
+        let file_path = files_dir.join(&trashed_name);
+        if fs::symlink_metadata(&file_path).is_ok() {
+            continue;
+        }
+        let mut info_file = match fs::OpenOptions::new().write(true).create_new(true).open(&info_path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error)
+        };
+        let result = info_file.write_all(info.as_bytes()).and_then(|_| fs::rename(&path, &file_path));
+        if let Err(error) = result {
+            let _ = fs::remove_file(&info_path);
+            return Err(error);
+        }
+        return Ok(TrashItem {
+            trash_dir,
+            name: trashed_name,
+            original_path: path,
+            deletion_date: deletion_date.to_string().replace('T', " ")
+        });
+    }
+    unreachable!()
+}
+
+/// all the items in the home trash and in the trashes of the mounted filesystems
+pub fn list() -> Vec<TrashItem> {
+    let mut items = Vec::new();
+    for trash_dir in trash_dirs() {
+        let top_dir = if Some(&trash_dir) == home_trash_dir().as_ref() { None } else { top_dir_of_trash(&trash_dir) };
+        let Ok(entries) = fs::read_dir(trash_dir.join("info")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().map(|extension| extension != INFO_EXTENSION).unwrap_or(true) {
+                continue;
+            }
+            let Some(name) = path.file_stem().map(|name| name.to_string_lossy().to_string()) else {
+                continue;
+            };
+            if let Some((original_path, deletion_date)) = read_info(&path) {
+                let original_path = match &top_dir {
+                    Some(top_dir) if original_path.is_relative() => top_dir.join(original_path),
+                    _ => original_path
+                };
+                items.push(TrashItem { trash_dir: trash_dir.clone(), name, original_path, deletion_date });
+            }
+        }
+    }
+    items
+}
+
+pub fn restore(item: &TrashItem) -> io::Result<()> {
+    if fs::symlink_metadata(&item.original_path).is_ok() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} already exists", item.original_path.display())));
+    }
+    if let Some(parent) = item.original_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(item.file_path(), &item.original_path)?;
+    fs::remove_file(item.info_path())
+}
+
+/// where the items on the home filesystem go
+fn home_trash_dir() -> Option<PathBuf> {
+    let data_home = match env::var_os("XDG_DATA_HOME") {
+        Some(data_home) if !data_home.is_empty() => PathBuf::from(data_home),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".local/share")
+    };
+    Some(data_home.join("Trash"))
+}
+
+fn user_id() -> io::Result<u32> {
+    Ok(fs::metadata("/proc/self")?.uid())
+}
+
+/// the trash directory for the path and the top directory its original path is stored relative to.
+/// other mounts use `$topdir/.Trash/$uid` when the administrator prepared it and `$topdir/.Trash-$uid` otherwise
+fn trash_dir_for(path: &Path) -> io::Result<(PathBuf, Option<PathBuf>)> {
+    let home_trash = home_trash_dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "the home directory is unknown"))?;
+    let device = fs::symlink_metadata(path)?.dev();
+    let home_device = existing_ancestor(&home_trash).map(fs::metadata).transpose()?.map(|metadata| metadata.dev());
+    if home_device == Some(device) {
+        return Ok((home_trash, None));
+    }
+
+    let top_dir = mount_point_of(path, device);
+    let uid = user_id()?;
+    let admin_trash = top_dir.join(".Trash");
+    if let Ok(metadata) = fs::symlink_metadata(&admin_trash) {
+        if metadata.is_dir() && metadata.mode() & STICKY_BIT != 0 {
+            let trash_dir = admin_trash.join(uid.to_string());
+            if create_private_dir(&trash_dir).is_ok() {
+                return Ok((trash_dir, Some(top_dir)));
+            }
+        }
+    }
+    let trash_dir = top_dir.join(format!(".Trash-{uid}"));
+    create_private_dir(&trash_dir)?;
+    Ok((trash_dir, Some(top_dir)))
+}
+
+fn trash_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = home_trash_dir().into_iter().collect();
+    let Ok(uid) = user_id() else {
+        return dirs;
+    };
+    for mount_point in mount_points() {
+        for trash_dir in [mount_point.join(".Trash").join(uid.to_string()), mount_point.join(format!(".Trash-{uid}"))] {
+            if trash_dir.is_dir() && !dirs.contains(&trash_dir) {
+                dirs.push(trash_dir);
+            }
+        }
+    }
+    dirs
+}
+
+fn top_dir_of_trash(trash_dir: &Path) -> Option<PathBuf> {
+    let parent = trash_dir.parent()?;
+    if parent.file_name().map(|name| name == ".Trash").unwrap_or(false) {
+        parent.parent().map(Path::to_path_buf)
+    }
+    else {
+        Some(parent.to_path_buf())
+    }
+}
+
+fn mount_points() -> Vec<PathBuf> {
+    let Ok(mounts) = fs::read_to_string("/proc/self/mounts") else {
+        return Vec::new();
+    };
+    mounts.lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(unescape_mount_point)
+        .collect()
+}
+
+/// the mount table escapes spaces and a few other characters as octal sequences like `\040`
+pub fn unescape_mount_point(mount_point: &str) -> PathBuf {
+    let bytes = mount_point.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes.get(index + 1..index + 4)
+            .and_then(|octal| std::str::from_utf8(octal).ok())
+            .and_then(|octal| u8::from_str_radix(octal, 8).ok());
+        match (bytes[index], escaped) {
+            (b'\\', Some(byte)) => {
+                unescaped.push(byte);
+                index += 4;
+            },
+            (byte, _) => {
+                unescaped.push(byte);
+                index += 1;
+            }
+        }
+    }
+    PathBuf::from(std::ffi::OsString::from_vec(unescaped))
+}
+
+/// walks up from the path until the parent is on another device
+fn mount_point_of(path: &Path, device: u64) -> PathBuf {
+    let mut top_dir = path;
+    while let Some(parent) = top_dir.parent() {
+        match fs::metadata(parent) {
+            Ok(metadata) if metadata.dev() == device => top_dir = parent,
+            _ => break
+        }
+    }
+    top_dir.to_path_buf()
+}
+
+fn existing_ancestor(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|ancestor| ancestor.exists())
+}
+
+fn create_private_dir(path: &Path) -> io::Result<()> {
+    if path.is_dir() {
+        return Ok(());
+    }
+    fs::DirBuilder::new().recursive(true).mode(0o700).create(path)
+}
+
+fn numbered_name(name: &Path, attempt: u32) -> String {
+    if attempt == 1 {
+        return name.to_string_lossy().to_string();
+    }
+    match (name.file_stem(), name.extension()) {
+        (Some(stem), Some(extension)) => format!("{}.{attempt}.{}", stem.to_string_lossy(), extension.to_string_lossy()),
+        _ => format!("{}.{attempt}", name.to_string_lossy())
+    }
+}
+
+fn read_info(path: &Path) -> Option<(PathBuf, String)> {
+    let contents = fs::read_to_string(path).ok()?;
+    let mut original_path = None;
+    let mut deletion_date = String::new();
+    let mut in_group = false;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_group = line == "[Trash Info]";
+        }
+        else if in_group {
+            if let Some(value) = line.strip_prefix("Path=") {
+                original_path = Some(decode_path(value));
+            }
+            else if let Some(value) = line.strip_prefix("DeletionDate=") {
+                deletion_date = value.replace('T', " ");
+            }
+        }
+    }
+    Some((original_path?, deletion_date))
+}
+
+pub fn encode_path(path: &Path) -> String {
+    let mut encoded = String::new();
+    for &byte in path.as_os_str().as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            encoded.push(byte as char);
+        }
+        else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+pub fn decode_path(value: &str) -> PathBuf {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes.get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            },
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    PathBuf::from(std::ffi::OsString::from_vec(decoded))
+}
+
+/// the local path of a `file://` uri, the host is ignored
+pub fn path_from_uri(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    Some(decode_path(&rest[rest.find('/').unwrap_or(rest.len())..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+
+    use super::*;
+
+    #[test]
+    fn paths_survive_encoding() {
+        let paths = [
+            PathBuf::from("/home/user/with space"),
+            PathBuf::from("/tmp/100%/%41"),
+            PathBuf::from("/tmp/line\nbreak"),
+            PathBuf::from("/tmp/ünïcode"),
+            PathBuf::from(OsString::from_vec(vec![b'/', b't', 0xff, 0xfe, b'x']))
+        ];
+        for path in paths {
+            let encoded = encode_path(&path);
+            assert!(encoded.bytes().all(|byte| byte.is_ascii_graphic()), "{encoded} isn't plain ascii");
+            assert_eq!(decode_path(&encoded), path);
+        }
+        assert_eq!(encode_path(Path::new("/a b/100%")), "/a%20b/100%25");
+        // a % that doesn't start an escape is kept as it is
+        assert_eq!(decode_path("/a%zz%4"), PathBuf::from("/a%zz%4"));
+    }
+
+    #[test]
+    fn uris_are_decoded() {
+        assert_eq!(path_from_uri("file:///a%20b/c"), Some(PathBuf::from("/a b/c")));
+        assert_eq!(path_from_uri("file://host/a%0Ab"), Some(PathBuf::from("/a\nb")));
+        assert_eq!(path_from_uri("/a/b"), None);
+    }
+}
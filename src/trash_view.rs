@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use crate::trash::{self, TrashItem};
+
+pub enum TrashAction {
+    Restore(Vec<TrashItem>),
+    Delete(Vec<TrashItem>)
+}
+
+pub struct TrashView {
+    items: Vec<TrashItem>,
+    selected: HashSet<usize>
+}
+
+impl TrashView {
+    pub fn new() -> Self {
+        let mut view = Self {
+            items: Vec::new(),
+            selected: HashSet::new()
+        };
+        view.refresh();
+        view
+    }
+
+    pub fn refresh(&mut self) {
+        self.items = trash::list();
+        self.items.sort_by(|a, b| b.deletion_date.cmp(&a.deletion_date));
+        self.selected.clear();
+    }
+
+    /// returns false when the window was closed
+    pub fn show(&mut self, ctx: &egui::Context, action: &mut Option<TrashAction>) -> bool {
+        let mut open = true;
+        egui::Window::new("Trash")
+            .open(&mut open)
+            .default_size(egui::vec2(500.0, 300.0))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("refresh").clicked() {
+                        self.refresh();
+                    }
+                    ui.separator();
+                    let any_selected = !self.selected.is_empty();
+                    if ui.add_enabled(any_selected, egui::Button::new("restore")).clicked() {
+                        *action = Some(TrashAction::Restore(self.take_selected()));
+                    }
+                    if ui.add_enabled(any_selected, egui::Button::new("delete permanently")).clicked() {
+                        *action = Some(TrashAction::Delete(self.take_selected()));
+                    }
+                    if ui.add_enabled(!self.items.is_empty(), egui::Button::new("empty trash")).clicked() {
+                        self.selected.clear();
+                        *action = Some(TrashAction::Delete(std::mem::take(&mut self.items)));
+                    }
+                });
+                ui.separator();
+                if self.items.is_empty() {
+                    ui.weak("The trash is empty");
+                    return;
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("trash_items").striped(true).num_columns(3).show(ui, |ui| {
+                        ui.strong("Name");
+                        ui.strong("Original location");
+                        ui.strong("Deleted");
+                        ui.end_row();
+                        for (index, item) in self.items.iter().enumerate() {
+                            let name = item.original_path.file_name().unwrap_or(item.original_path.as_os_str()).to_string_lossy();
+                            let selected = self.selected.contains(&index);
+                            if ui.selectable_label(selected, name).clicked() {
+                                if selected {
+                                    self.selected.remove(&index);
+                                }
+                                else {
+                                    self.selected.insert(index);
+                                }
+                            }
+                            let location = item.original_path.parent().map(|parent| parent.to_string_lossy().to_string()).unwrap_or_default();
+                            ui.label(location);
+                            ui.label(&item.deletion_date);
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        open
+    }
+
+    /// removes the selected items from the view, the action on them runs in the background
+    fn take_selected(&mut self) -> Vec<TrashItem> {
+        let mut taken = Vec::new();
+        let mut kept = Vec::new();
+        for (index, item) in self.items.drain(..).enumerate() {
+            if self.selected.contains(&index) {
+                taken.push(item);
+            }
+            else {
+                kept.push(item);
+            }
+        }
+        self.items = kept;
+        self.selected.clear();
+        taken
+    }
+}
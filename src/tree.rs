@@ -0,0 +1,144 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use egui::collapsing_header::CollapsingState;
+
+use crate::details;
+use crate::ignore::{Gitignore, IgnoreSettings};
+
+struct Node {
+    path: PathBuf,
+    name: String,
+    /// None until the node is expanded for the first time
+    children: Option<Vec<Node>>
+}
+
+impl Node {
+    fn new(path: PathBuf) -> Self {
+        let name = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().to_string();
+        Self { path, name, children: None }
+    }
+
+    /// the loaded node of the path, if it is in this one
+    fn find_mut(&mut self, path: &Path) -> Option<&mut Node> {
+        if self.path == path {
+            return Some(self);
+        }
+        if !path.starts_with(&self.path) {
+            return None;
+        }
+        self.children.as_mut()?.iter_mut().find_map(|child| child.find_mut(path))
+    }
+}
+
+/// the folder tree next to the listings, the folders are read when they are expanded
+pub struct DirectoryTree {
+    root: Node,
+    ignore: IgnoreSettings,
+    /// the directory that was revealed last, the tree reveals the current directory again when it changes
+    revealed: Option<PathBuf>,
+    scroll_to_current: bool
+}
+
+impl DirectoryTree {
+    pub fn new(ignore: IgnoreSettings) -> Self {
+        Self {
+            root: Node::new(PathBuf::from("/")),
+            ignore,
+            revealed: None,
+            scroll_to_current: false
+        }
+    }
+
+    /// the folders are read again with the new settings when they are shown
+    pub fn set_ignore(&mut self, ignore: IgnoreSettings) {
+        self.ignore = ignore;
+        self.root.children = None;
+    }
+
+    /// reads the folders in the directory again if they were read before, the expanded ones keep their children
+    pub fn refresh(&mut self, directory: &Path) {
+        let ignore = &self.ignore;
+        let Some(node) = self.root.find_mut(directory) else {
+            return;
+        };
+        let Some(mut old_children) = node.children.take() else {
+            return;
+        };
+        let mut children = read_children(directory, ignore);
+        for child in &mut children {
+            if let Some(old) = old_children.iter_mut().find(|old| old.path == child.path) {
+                child.children = old.children.take();
+            }
+        }
+        node.children = Some(children);
+    }
+
+    /// returns the folder that was clicked
+    pub fn show(&mut self, ui: &mut egui::Ui, current: &Path) -> Option<PathBuf> {
+        if self.revealed.as_deref() != Some(current) {
+            for ancestor in current.ancestors().skip(1) {
+                let mut state = CollapsingState::load_with_default_open(ui.ctx(), node_id(ancestor), false);
+                state.set_open(true);
+                state.store(ui.ctx());
+            }
+            self.revealed = Some(current.to_path_buf());
+            self.scroll_to_current = true;
+        }
+
+        let mut clicked = None;
+        egui::ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
+            show_node(ui, &mut self.root, current, &self.ignore, &mut self.scroll_to_current, &mut clicked);
+        });
+        clicked
+    }
+}
+
+fn node_id(path: &Path) -> egui::Id {
+    egui::Id::new("directory_tree").with(path)
+}
+
+fn show_node(ui: &mut egui::Ui, node: &mut Node, current: &Path, ignore: &IgnoreSettings, scroll_to_current: &mut bool, clicked: &mut Option<PathBuf>) {
+    let mut header = |ui: &mut egui::Ui| {
+        let response = ui.selectable_label(node.path == current, &node.name).on_hover_text(node.path.to_string_lossy());
+        if response.clicked() {
+            *clicked = Some(node.path.clone());
+        }
+        if *scroll_to_current && node.path == current {
+            response.scroll_to_me(Some(egui::Align::Center));
+            *scroll_to_current = false;
+        }
+    };
+    // folders that are known to have no subfolders don't get an expand button
+    if node.children.as_ref().map(Vec::is_empty).unwrap_or(false) {
+        ui.horizontal(|ui| {
+            ui.add_space(ui.spacing().indent);
+            header(ui);
+        });
+        return;
+    }
+    CollapsingState::load_with_default_open(ui.ctx(), node_id(&node.path), false)
+        .show_header(ui, header)
+        .body(|ui| {
+            let children = node.children.get_or_insert_with(|| read_children(&node.path, ignore));
+            for child in children {
+                show_node(ui, child, current, ignore, scroll_to_current, clicked);
+            }
+        });
+}
+
+/// the subfolders the listings would show, sorted by name
+fn read_children(directory: &Path, ignore: &IgnoreSettings) -> Vec<Node> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    let gitignores = if ignore.use_gitignore { Gitignore::for_directory(directory) } else { Vec::new() };
+    let mut children: Vec<Node> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && !ignore.hides(path, true, &gitignores))
+        .map(Node::new)
+        .collect();
+    children.sort_by(|a, b| details::natural_cmp(&a.name, &b.name));
+    children
+}
@@ -1,8 +1,22 @@
-use std::path::{PathBuf, self};
+use std::path::{Path, PathBuf, self};
 
+use crate::details;
+use crate::file_list::{IconSize, ViewMode};
 use crate::gui_extension::*;
+use crate::ignore;
+use crate::jobs;
+use crate::journal;
+use crate::location;
+use crate::quick_filter;
+use crate::settings::{Settings, Theme};
 
-pub fn path_navigation_bar(ui: &mut egui::Ui, path: &PathBuf, width: f32) -> Option<path::PathBuf> {
+pub enum PathBarAction {
+    Navigate(PathBuf),
+    /// the bar was clicked next to the breadcrumbs, it turns into a text field
+    Edit
+}
+
+pub fn path_navigation_bar(ui: &mut egui::Ui, path: &PathBuf, width: f32) -> Option<PathBarAction> {
     let component_padding = egui::vec2(5.0, 7.0);
     let total_component_padding = component_padding * 2.0;
     let height = ui.get_text_style_height(egui::style::TextStyle::Button) + component_padding.y * 2.0 ;
@@ -10,59 +24,526 @@ pub fn path_navigation_bar(ui: &mut egui::Ui, path: &PathBuf, width: f32) -> Opt
     let rect = ui.calculate_rect_from_size(size);
     let visuals = ui.visuals();
     ui.painter().rect_stroke(rect, egui::Rounding::none(), visuals.window_stroke);
+    let background = ui.interact(rect, ui.id().with("path_bar"), egui::Sense::click());
+
+    // every component with the path up to it, the root has no name of its own
+    let mut components = Vec::new();
+    let mut component_path = path::PathBuf::new();
+    for component in path.iter() {
+        component_path.push(component);
+        let text = component.to_string_lossy();
+        let text = text.trim_matches(|c| c == '/' || c == '\\');
+        if !text.is_empty() {
+            let galley = ui.str_to_text_galley(text, egui::TextStyle::Button);
+            components.push((galley, component_path.clone()));
+        }
+    }
+    let arrow_head_width = ui.get_text_style_height(egui::TextStyle::Button) / 3.6;
+    let arrow_width = arrow_head_width + total_component_padding.x;
+    let component_width = |galley: &egui::widget_text::WidgetTextGalley, arrow: bool| {
+        galley.size().x + total_component_padding.x + if arrow { arrow_width } else { 0.0 }
+    };
+
+    // the leading components are collapsed into a menu until the rest fits
+    let overflow_galley = ui.str_to_text_galley("…", egui::TextStyle::Button);
+    let overflow_width = component_width(&overflow_galley, false);
+    let mut total: f32 = components.iter().enumerate().map(|(index, (galley, _))| component_width(galley, index + 1 < components.len())).sum();
+    let mut collapsed = 0;
+    while total > width && collapsed + 1 < components.len() {
+        total -= component_width(&components[collapsed].0, true);
+        if collapsed == 0 {
+            total += overflow_width;
+        }
+        collapsed += 1;
+    }
 
     let mut path_component_clicked = None;
 
     ui.horizontal(|ui| {
         ui.style_mut().spacing.item_spacing.x = 0.0;
-        let mut it = path.iter().peekable();
-        let mut component_path = path::PathBuf::new();
-        while let Some(component) = it.next() {
-            let str = component.to_string_lossy();
-            let text = str.trim_matches(|c| c == '/' || c == '\\');
-            
-            if !text.is_empty() {
-                // TODO: make a separate path struct
-                let str = component_path.as_mut_os_string();
-                str.push(component);
-                str.push("/");
-                let show_arrow_head = it.peek().is_some();
-                let text = ui.str_to_text_galley(text, egui::TextStyle::Button);
-                let text_size = text.size();
-                let mut button_arrow_spacing = 0.0;
-                let mut arrow_head_width = 0.0;
-                if show_arrow_head {
-                    button_arrow_spacing = total_component_padding.x;
-                    arrow_head_width = text_size.y / 3.6;
-                }
-                let desired_size = text_size + total_component_padding + egui::vec2(button_arrow_spacing + arrow_head_width, 0.0);
-                
-                let (rect, response) = ui.allocate_at_least(desired_size, egui::Sense::click());
-                response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, text.text()));
-                if response.clicked() {
-                    path_component_clicked = Some(component_path.clone());
-                }
+        if collapsed > 0 {
+            let response = path_component(ui, &overflow_galley, component_padding);
+            let ancestors = || path.ancestors().skip(components.len() - collapsed).map(Path::to_path_buf).collect();
+            if let Some(folder) = folder_menu(ui, &response, ancestors, None) {
+                path_component_clicked = Some(folder);
+            }
+        }
+        for (index, (galley, component_path)) in components.iter().enumerate().skip(collapsed) {
+            let response = path_component(ui, galley, component_padding);
+            if response.clicked() {
+                path_component_clicked = Some(component_path.clone());
+            }
 
+            // the arrow after a component lists the folders in it, the next component among them
+            if let Some((_, next)) = components.get(index + 1) {
+                let (rect, response) = ui.allocate_exact_size(egui::vec2(arrow_width, galley.size().y + total_component_padding.y), egui::Sense::click());
                 if ui.is_rect_visible(rect) {
                     let visuals = ui.style().interact(&response);
-                    let (text_rect, arrow_head_rect) = split_rect_horizontally(rect, button_arrow_spacing, component_padding, text_size.x, arrow_head_width);
                     if response.hovered() {
                         ui.painter().rect(rect, egui::Rounding::none(), visuals.weak_bg_fill, egui::Stroke::NONE);
                     }
-                    text.paint_with_visuals(ui.painter(), text_rect.min, visuals);
+                    let arrow_head_size = egui::vec2(arrow_head_width, arrow_head_width * 2.0);
+                    let arrow_head_pos = egui::Align2::CENTER_CENTER.align_size_within_rect(arrow_head_size, rect).min;
+                    let stoke = egui::Stroke::new(2.0, visuals.fg_stroke.color);
+                    ui.painter().arrow_head(arrow_head_pos, arrow_head_size, stoke);
+                }
+                if let Some(folder) = folder_menu(ui, &response, || subfolders(component_path), Some(next)) {
+                    path_component_clicked = Some(folder);
+                }
+            }
+        }
+    });
 
-                    if show_arrow_head {
-                        let arrow_head_size = egui::vec2(arrow_head_width, arrow_head_width * 2.0);
-                        let arrow_head_pos = ui.layout().align_size_within_rect(arrow_head_size, arrow_head_rect).min;
-                        let stoke = egui::Stroke::new(2.0, visuals.fg_stroke.color);
-                        ui.painter().arrow_head(arrow_head_pos, arrow_head_size, stoke);
-                    }
+    match path_component_clicked {
+        Some(path) => Some(PathBarAction::Navigate(path)),
+        None if background.clicked() => Some(PathBarAction::Edit),
+        None => None
+    }
+}
+
+fn path_component(ui: &mut egui::Ui, galley: &egui::widget_text::WidgetTextGalley, padding: egui::Vec2) -> egui::Response {
+    let (rect, response) = ui.allocate_at_least(galley.size() + padding * 2.0, egui::Sense::click());
+    response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, galley.text()));
+    if ui.is_rect_visible(rect) {
+        let visuals = ui.style().interact(&response);
+        if response.hovered() {
+            ui.painter().rect(rect, egui::Rounding::none(), visuals.weak_bg_fill, egui::Stroke::NONE);
+        }
+        galley.clone().paint_with_visuals(ui.painter(), rect.min + padding, visuals);
+    }
+    response
+}
+
+/// a dropdown under the button listing the folders, they are read once when it opens and kept while it is open.
+/// returns the one that was clicked
+fn folder_menu(ui: &mut egui::Ui, response: &egui::Response, folders: impl FnOnce() -> Vec<PathBuf>, current: Option<&PathBuf>) -> Option<PathBuf> {
+    let popup_id = response.id.with("folders");
+    if response.clicked() {
+        ui.memory_mut(|memory| memory.toggle_popup(popup_id));
+        if ui.memory(|memory| memory.is_popup_open(popup_id)) {
+            let folders = folders();
+            ui.ctx().data_mut(|d| d.insert_temp(popup_id, folders));
+        }
+    }
+    let mut chosen = None;
+    egui::popup_below_widget(ui, popup_id, response, |ui| {
+        ui.set_min_width(150.0);
+        let folders: Vec<PathBuf> = ui.ctx().data(|d| d.get_temp(popup_id)).unwrap_or_default();
+        if folders.is_empty() {
+            ui.weak("no folders");
+        }
+        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            for folder in folders {
+                let name = folder.file_name().unwrap_or(folder.as_os_str()).to_string_lossy().to_string();
+                if ui.selectable_label(Some(&folder) == current, name).clicked() {
+                    chosen = Some(folder);
+                }
+            }
+        });
+    });
+    if !ui.memory(|memory| memory.is_popup_open(popup_id)) {
+        ui.ctx().data_mut(|d| d.remove::<Vec<PathBuf>>(popup_id));
+    }
+    chosen
+}
+
+/// the folders in the directory that aren't hidden, sorted by name
+fn subfolders(directory: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    let mut folders: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && !ignore::is_hidden(&path.file_name().unwrap_or_default().to_string_lossy()))
+        .collect();
+    folders.sort_by(|a, b| details::natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+    folders
+}
+
+pub enum LocationAction {
+    Submit(String),
+    Cancel
+}
+
+/// the folders of the typed folder, they are only read again once another folder is typed
+#[derive(Clone, Default)]
+struct CompletionCache {
+    directory: Option<String>,
+    names: Vec<String>,
+    text: String,
+    completions: Vec<String>
+}
+
+impl CompletionCache {
+    fn id(field: egui::Id) -> egui::Id {
+        field.with("completion cache")
+    }
+
+    fn completions(ctx: &egui::Context, field: egui::Id, text: &str, current: &Path) -> Vec<String> {
+        let mut cache: Self = ctx.data(|d| d.get_temp(Self::id(field))).unwrap_or_default();
+        if cache.directory.is_some() && cache.text == text {
+            return cache.completions;
+        }
+        let directory = location::split_typed(text).0;
+        if cache.directory.as_deref() != Some(directory) {
+            cache.names = location::folder_names(directory, current);
+            cache.directory = Some(directory.to_owned());
+        }
+        cache.completions = location::completions(text, &cache.names);
+        cache.text = text.to_owned();
+        let completions = cache.completions.clone();
+        ctx.data_mut(|d| d.insert_temp(Self::id(field), cache));
+        completions
+    }
+}
+
+/// the location bar while a path is typed into it, tab completes the folder name
+/// and the folders that match are listed under it
+pub fn location_field(ui: &mut egui::Ui, text: &mut String, id: egui::Id, current: &Path, width: f32) -> Option<LocationAction> {
+    let completions = CompletionCache::completions(ui.ctx(), id, text, current);
+    let focused = ui.memory(|memory| memory.has_focus(id));
+    let mut picked = None;
+    if focused && ui.input_mut(|input| input.consume_key(egui::Modifiers::NONE, egui::Key::Tab)) {
+        picked = location::complete(&completions).filter(|completion| completion != text);
+    }
+
+    let output = egui::TextEdit::singleline(text).id(id).lock_focus(true).desired_width(width).show(ui);
+    let response = output.response;
+    let offered = completions.len() > 1 || completions.first().map(|completion| completion != text).unwrap_or(false);
+    if focused && offered {
+        egui::Area::new(id.with("completions"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(response.rect.left_bottom())
+            .show(ui.ctx(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(response.rect.width());
+                    egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                        for completion in &completions {
+                            let name = completion.trim_end_matches('/').rsplit('/').next().unwrap_or(completion);
+                            if ui.selectable_label(false, name).clicked() {
+                                picked = Some(completion.clone());
+                            }
+                        }
+                    });
+                });
+            });
+    }
+
+    // picking a completion keeps the field open with the cursor at the end, even when clicking it took the focus
+    if let Some(completion) = picked {
+        *text = completion;
+        let mut state = output.state;
+        let end = egui::text::CCursor::new(text.chars().count());
+        state.set_ccursor_range(Some(egui::text_edit::CCursorRange::one(end)));
+        state.store(ui.ctx(), id);
+        response.request_focus();
+        return None;
+    }
+    if response.lost_focus() {
+        if ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+            return Some(LocationAction::Submit(text.clone()));
+        }
+        return Some(LocationAction::Cancel);
+    }
+    None
+}
+
+/// starts editing the location with the whole path selected
+pub fn edit_location(ctx: &egui::Context, id: egui::Id, text: &str) {
+    let mut state = egui::text_edit::TextEditState::load(ctx, id).unwrap_or_default();
+    let selection = egui::text_edit::CCursorRange::two(
+        egui::text::CCursor::new(0),
+        egui::text::CCursor::new(text.chars().count())
+    );
+    state.set_ccursor_range(Some(selection));
+    state.store(ctx, id);
+    // the folders may have changed since the location was edited last
+    ctx.data_mut(|d| d.remove::<CompletionCache>(CompletionCache::id(id)));
+    ctx.memory_mut(|memory| memory.request_focus(id));
+}
+
+pub enum TabBarAction {
+    Select(usize),
+    Close(usize),
+    Move(usize, usize),
+    New
+}
+
+/// the tabs are identified by their id so a dragged tab keeps its drag state while it is reordered
+pub fn tab_bar(ui: &mut egui::Ui, tabs: &[(egui::Id, String)], active: usize) -> Option<TabBarAction> {
+    let mut action = None;
+    ui.horizontal(|ui| {
+        let mut rects = Vec::with_capacity(tabs.len());
+        let mut dragged = None;
+        for (index, (id, title)) in tabs.iter().enumerate() {
+            let frame = egui::Frame::none()
+                .inner_margin(egui::Margin::symmetric(6.0, 2.0))
+                .fill(if index == active { ui.visuals().selection.bg_fill } else { egui::Color32::TRANSPARENT });
+            let inner = frame.show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(title);
+                    ui.small_button("×").on_hover_text("Close tab (Ctrl+W)").clicked()
+                }).inner
+            });
+            let rect = inner.response.rect;
+            let response = ui.interact(rect, *id, egui::Sense::click_and_drag());
+            if inner.inner || response.middle_clicked() {
+                action = Some(TabBarAction::Close(index));
+            }
+            else if response.clicked() || response.drag_started() {
+                action = Some(TabBarAction::Select(index));
+            }
+            if response.dragged() {
+                dragged = Some(index);
+            }
+            rects.push(rect);
+        }
+        if let (Some(from), Some(pointer)) = (dragged, ui.ctx().pointer_interact_pos()) {
+            let target = rects.iter().position(|rect| rect.x_range().contains(&pointer.x));
+            if let Some(to) = target {
+                if to != from {
+                    action = Some(TabBarAction::Move(from, to));
                 }
             }
         }
+        if ui.button("+").on_hover_text("New tab (Ctrl+T)").clicked() {
+            action = Some(TabBarAction::New);
+        }
+    });
+    action
+}
+
+/// shows the history entries when the button is right clicked, returns the amount of steps to the chosen entry
+pub fn history_menu<'a>(response: egui::Response, entries: impl Iterator<Item = &'a PathBuf>) -> Option<usize> {
+    let mut chosen = None;
+    response.context_menu(|ui| {
+        for (index, entry) in entries.enumerate() {
+            let name = entry.file_name().unwrap_or(entry.as_os_str()).to_string_lossy();
+            if ui.button(name).on_hover_text(entry.to_string_lossy()).clicked() {
+                chosen = Some(index + 1);
+                ui.close_menu();
+            }
+        }
+    });
+    chosen
+}
+
+/// returns true when the failed and cancelled jobs should be cleared
+pub fn jobs_panel(ui: &mut egui::Ui, queue: &jobs::JobQueue) -> bool {
+    let mut clear = false;
+    ui.horizontal(|ui| {
+        ui.strong("Jobs");
+        if ui.small_button("clear").clicked() {
+            clear = true;
+        }
     });
+    egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+        for job in queue.jobs() {
+            ui.push_id(job.id, |ui| {
+                let state = job.state();
+                let progress = job.progress();
+                ui.horizontal(|ui| {
+                    ui.label(&job.description);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        match &state {
+                            jobs::JobState::Running => {
+                                if ui.small_button("cancel").clicked() {
+                                    job.cancel();
+                                }
+                                if ui.small_button("pause").clicked() {
+                                    job.pause();
+                                }
+                            },
+                            jobs::JobState::Paused => {
+                                if ui.small_button("cancel").clicked() {
+                                    job.cancel();
+                                }
+                                if ui.small_button("resume").clicked() {
+                                    job.resume();
+                                }
+                            },
+                            jobs::JobState::Finished => { ui.label("done"); },
+                            jobs::JobState::Cancelled => { ui.label("cancelled"); },
+                            jobs::JobState::Failed(error) => { ui.colored_label(ui.visuals().error_fg_color, error); },
+                        }
+                    });
+                });
+                if !state.is_done() {
+                    let mut text = format!(
+                        "{} / {} files, {} / {}",
+                        progress.done_files,
+                        progress.total_files,
+                        format_size(progress.done_bytes),
+                        format_size(progress.total_bytes)
+                    );
+                    if let Some(eta) = progress.eta() {
+                        text.push_str(&format!(", {} left", format_duration(eta)));
+                    }
+                    ui.add(egui::ProgressBar::new(progress.fraction()).text(text));
+                    if let Some(current_file) = &progress.current_file {
+                        ui.weak(current_file.to_string_lossy());
+                    }
+                }
+            });
+            ui.separator();
+        }
+    });
+    clear
+}
+
+pub enum JournalAction {
+    Undo,
+    Redo
+}
+
+pub fn journal_window(ctx: &egui::Context, open: &mut bool, journal: &journal::Journal) -> Option<JournalAction> {
+    let mut action = None;
+    egui::Window::new("History")
+        .open(open)
+        .default_size(egui::vec2(300.0, 300.0))
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!journal.is_busy() && journal.undo_entries().next().is_some(), egui::Button::new("undo")).on_hover_text("Ctrl+Z").clicked() {
+                    action = Some(JournalAction::Undo);
+                }
+                if ui.add_enabled(!journal.is_busy() && journal.redo_entries().next().is_some(), egui::Button::new("redo")).on_hover_text("Ctrl+Shift+Z").clicked() {
+                    action = Some(JournalAction::Redo);
+                }
+            });
+            ui.separator();
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                // the redo entries are shown above the current state like in most editors
+                let redo: Vec<&journal::Operation> = journal.redo_entries().collect();
+                for operation in redo.into_iter().rev() {
+                    ui.weak(operation.description());
+                }
+                for (index, operation) in journal.undo_entries().enumerate() {
+                    if index == 0 {
+                        ui.strong(operation.description());
+                    }
+                    else {
+                        ui.label(operation.description());
+                    }
+                }
+            });
+        });
+    action
+}
+
+/// returns whether a setting was changed
+pub fn settings_window(ctx: &egui::Context, open: &mut bool, settings: &mut Settings) -> bool {
+    let mut changed = false;
+    egui::Window::new("Settings")
+        .open(open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("settings").num_columns(2).spacing(egui::vec2(12.0, 6.0)).show(ui, |ui| {
+                ui.label("theme");
+                ui.horizontal(|ui| {
+                    for theme in Theme::ALL {
+                        changed |= ui.selectable_value(&mut settings.theme, theme, theme.title()).changed();
+                    }
+                });
+                ui.end_row();
+
+                ui.label("view");
+                ui.horizontal(|ui| {
+                    changed |= ui.selectable_value(&mut settings.view_mode, ViewMode::List, "list").changed();
+                    changed |= ui.selectable_value(&mut settings.view_mode, ViewMode::Details, "details").changed();
+                    changed |= ui.selectable_value(&mut settings.view_mode, ViewMode::Grid, "grid").changed();
+                });
+                ui.end_row();
+
+                ui.label("icon size");
+                egui::ComboBox::from_id_source("settings_icon_size")
+                    .selected_text(settings.icon_size.title())
+                    .show_ui(ui, |ui| {
+                        for size in IconSize::ALL {
+                            changed |= ui.selectable_value(&mut settings.icon_size, size, size.title()).changed();
+                        }
+                    });
+                ui.end_row();
 
-    path_component_clicked
+                ui.label("sort by");
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source("settings_sort_column")
+                        .selected_text(settings.sort_column.title())
+                        .show_ui(ui, |ui| {
+                            for column in details::Column::ALL {
+                                changed |= ui.selectable_value(&mut settings.sort_column, column, column.title()).changed();
+                            }
+                        });
+                    changed |= ui.selectable_value(&mut settings.sort_order, details::SortOrder::Ascending, "ascending").changed();
+                    changed |= ui.selectable_value(&mut settings.sort_order, details::SortOrder::Descending, "descending").changed();
+                });
+                ui.end_row();
+
+                ui.label("");
+                changed |= ui.checkbox(&mut settings.folders_first, "folders first").changed();
+                ui.end_row();
+
+                ui.label("files");
+                ui.vertical(|ui| {
+                    changed |= ui.checkbox(&mut settings.show_hidden, "show hidden files").changed();
+                    changed |= ui.checkbox(&mut settings.use_gitignore, "hide what .gitignore ignores").changed();
+                });
+                ui.end_row();
+
+                ui.label("typing");
+                ui.horizontal(|ui| {
+                    changed |= ui.selectable_value(&mut settings.typing_filters, false, "jumps to the item").changed();
+                    changed |= ui.selectable_value(&mut settings.typing_filters, true, "filters the list").changed();
+                });
+                ui.end_row();
+
+                ui.label("on start");
+                changed |= ui.checkbox(&mut settings.restore_session, "open the tabs of the last session").changed();
+                ui.end_row();
+
+                ui.label("");
+                changed |= ui.checkbox(&mut settings.single_instance, "open paths in the running window")
+                    .on_hover_text("file-explorer --new-window always opens a window of its own")
+                    .changed();
+                ui.end_row();
+
+                ui.label("");
+                changed |= ui.checkbox(&mut settings.file_manager_service, "handle \"show in folder\" of other programs")
+                    .on_hover_text("registers org.freedesktop.FileManager1 on the session bus")
+                    .changed();
+                ui.end_row();
+            });
+        });
+    changed
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[unit])
+    }
+    else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+pub fn format_duration(duration: std::time::Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!("{}h {}m", seconds / 3600, seconds % 3600 / 60)
+    }
+    else if seconds >= 60 {
+        format!("{}m {}s", seconds / 60, seconds % 60)
+    }
+    else {
+        format!("{seconds}s")
+    }
 }
 
 pub fn error_dialog(ctx: &egui::Context, message: &str) -> bool {
@@ -145,6 +626,7 @@ impl RenamableLabelState {
 pub struct RenamableLabel<'a> {
     text: String,
     request_renaming: bool,
+    highlight: Vec<usize>,
     state: RenamableLabelState,
     ctx: &'a egui::Context,
     id: egui::Id
@@ -159,6 +641,7 @@ impl<'a> RenamableLabel<'a> {
         Self { 
             text,
             request_renaming: false,
+            highlight: Vec::new(),
             state,
             ctx,
             id
@@ -170,6 +653,16 @@ impl<'a> RenamableLabel<'a> {
         self
     }
 
+    /// the characters that are highlighted while the label isn't being renamed
+    pub fn highlight(&mut self, characters: &[usize]) -> &mut Self {
+        self.highlight = characters.to_vec();
+        self
+    }
+
+    pub fn is_renaming(&self) -> bool {
+        self.request_renaming || self.state.text.is_some()
+    }
+
     pub fn get_text(&self) -> &str {
         self.state.text.as_ref().unwrap_or(&self.text)
     }
@@ -209,7 +702,13 @@ impl<'a> RenamableLabel<'a> {
                 else {
                     ui.horizontal(|ui| {
                         ui.add_space(RENAMABLE_LABLE_INPUT_MARGIN.x);
-                        egui::InnerResponse::new(None, ui.label(self.text))
+                        let response = if self.highlight.is_empty() {
+                            ui.label(self.text)
+                        }
+                        else {
+                            ui.label(quick_filter::highlighted(&self.text, &self.highlight, ui.style(), ui.visuals().text_color()))
+                        };
+                        egui::InnerResponse::new(None, response)
                     }).inner
                 }
             }